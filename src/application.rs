@@ -237,9 +237,15 @@ impl DistroShelfApplication {
             }
         };
 
-        command_runner.output_tracker().enable();
-
         let root_store = RootStore::new(command_runner.clone());
+
+        // The capture can be toggled while the app is running
+        let settings = root_store.settings();
+        command_runner.set_output_capture(settings.boolean("command-log-capture"));
+        let command_runner_clone = command_runner.clone();
+        settings.connect_changed(Some("command-log-capture"), move |settings, key| {
+            command_runner_clone.set_output_capture(settings.boolean(key));
+        });
         root_store.start_background_tasks();
 
         self.set_root_store(root_store);
@@ -16,6 +16,248 @@ pub trait ContainerRuntime {
     async fn version(&self) -> anyhow::Result<String>;
     async fn usage(&self, container_id: &str) -> anyhow::Result<Usage>;
     async fn downloaded_images(&self) -> anyhow::Result<HashSet<String>>;
+    /// Lists every container known to the runtime, including the ones not created by distrobox
+    async fn list_all_containers(&self) -> anyhow::Result<Vec<RuntimeContainer>>;
+    /// Reads the addresses and published ports of a container from `inspect`
+    async fn network_info(&self, name: &str) -> anyhow::Result<NetworkInfo>;
+    /// Reads the mounts of a container from `inspect`
+    async fn mounts(&self, name: &str) -> anyhow::Result<Vec<Mount>>;
+    /// Checks whether an image is available locally, resolving short names like the runtime does
+    async fn image_exists(&self, image: &str) -> anyhow::Result<bool>;
+    /// Reads the ID of the image a container was created from, which stays the same
+    /// when its tag is pulled again and moves to another image
+    async fn container_image_id(&self, name: &str) -> anyhow::Result<String>;
+    /// Resolves a local image to a reference pinned to its digest, like `ubuntu@sha256:...`
+    async fn image_digest(&self, image: &str) -> anyhow::Result<String>;
+    /// Stops a container, including the ones not created by distrobox
+    async fn stop_container(&self, name: &str) -> anyhow::Result<()>;
+}
+
+/// A container as seen by the runtime, which may or may not be managed by distrobox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub state: String,
+    pub is_distrobox: bool,
+}
+
+impl RuntimeContainer {
+    pub fn is_running(&self) -> bool {
+        self.state.eq_ignore_ascii_case("running")
+    }
+}
+
+/// Parses the output of `ps -a --format json`.
+/// Podman prints a JSON array with `Names` and `Labels` as array/map, docker prints
+/// one object per line with comma separated strings instead.
+pub(crate) fn parse_container_list(output: &str) -> anyhow::Result<Vec<RuntimeContainer>> {
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(vec![]);
+    }
+    let values: Vec<serde_json::Value> = match serde_json::from_str(output) {
+        Ok(values) => values,
+        Err(_) => output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?,
+    };
+
+    let string_field = |value: &serde_json::Value, keys: &[&str]| -> String {
+        keys.iter()
+            .find_map(|key| value.get(key).and_then(|v| v.as_str()))
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let mut containers = Vec::with_capacity(values.len());
+    for value in values {
+        let name = match value.get("Names") {
+            Some(serde_json::Value::Array(names)) => names
+                .first()
+                .and_then(|name| name.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            Some(serde_json::Value::String(names)) => {
+                names.split(',').next().unwrap_or_default().to_string()
+            }
+            _ => String::new(),
+        };
+        let manager = match value.get("Labels") {
+            Some(serde_json::Value::Object(labels)) => labels
+                .get("manager")
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string()),
+            Some(serde_json::Value::String(labels)) => labels
+                .split(',')
+                .find_map(|label| label.strip_prefix("manager="))
+                .map(|v| v.to_string()),
+            _ => None,
+        };
+        containers.push(RuntimeContainer {
+            id: string_field(&value, &["Id", "ID"]),
+            name,
+            image: string_field(&value, &["Image"]),
+            state: string_field(&value, &["State"]),
+            is_distrobox: manager.as_deref() == Some("distrobox"),
+        });
+    }
+    Ok(containers)
+}
+
+/// How a container can be reached from the host
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NetworkInfo {
+    /// The container shares the host network namespace (the distrobox default),
+    /// so it has no address of its own and is reachable through the host's
+    pub host_network: bool,
+    pub ip_addresses: Vec<String>,
+    pub ports: Vec<PortMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMapping {
+    /// Port inside the container, with the protocol, like `22/tcp`
+    pub container_port: String,
+    pub host_ip: String,
+    pub host_port: String,
+}
+
+/// A path of the host (or a named volume) visible inside a container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    /// `bind` for host paths, `volume` for named volumes
+    pub kind: String,
+    pub source: String,
+    pub destination: String,
+    pub read_only: bool,
+}
+
+/// Reads the first container of the output of `inspect --type container`
+fn parse_inspected_container(output: &str) -> anyhow::Result<serde_json::Value> {
+    let value: serde_json::Value = serde_json::from_str(output.trim())?;
+    match value {
+        serde_json::Value::Array(values) => values
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No container found")),
+        value => Ok(value),
+    }
+}
+
+/// Parses the network settings from the output of `inspect --type container`
+pub(crate) fn parse_network_info(output: &str) -> anyhow::Result<NetworkInfo> {
+    let value = parse_inspected_container(output)?;
+
+    let host_network = value
+        .pointer("/HostConfig/NetworkMode")
+        .and_then(|mode| mode.as_str())
+        == Some("host");
+    let settings = value.get("NetworkSettings");
+
+    let mut ip_addresses: Vec<String> = vec![];
+    if !host_network {
+        let networks = settings
+            .and_then(|settings| settings.get("Networks"))
+            .and_then(|networks| networks.as_object());
+        let candidates = networks
+            .into_iter()
+            .flat_map(|networks| networks.values())
+            .chain(settings)
+            .filter_map(|network| network.get("IPAddress").and_then(|ip| ip.as_str()));
+        for ip in candidates {
+            if !ip.is_empty() && !ip_addresses.iter().any(|known| known == ip) {
+                ip_addresses.push(ip.to_string());
+            }
+        }
+    }
+
+    let mut ports = vec![];
+    if let Some(port_map) = settings
+        .and_then(|settings| settings.get("Ports"))
+        .and_then(|ports| ports.as_object())
+    {
+        for (container_port, bindings) in port_map {
+            // Exposed but unpublished ports have no bindings
+            let Some(bindings) = bindings.as_array() else {
+                continue;
+            };
+            for binding in bindings {
+                let field = |key: &str| {
+                    binding
+                        .get(key)
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                ports.push(PortMapping {
+                    container_port: container_port.clone(),
+                    host_ip: field("HostIp"),
+                    host_port: field("HostPort"),
+                });
+            }
+        }
+    }
+
+    Ok(NetworkInfo {
+        host_network,
+        ip_addresses,
+        ports,
+    })
+}
+
+/// Parses the mounts from the output of `inspect --type container`
+pub(crate) fn parse_mounts(output: &str) -> anyhow::Result<Vec<Mount>> {
+    let value = parse_inspected_container(output)?;
+    let Some(mounts) = value.get("Mounts").and_then(|mounts| mounts.as_array()) else {
+        return Ok(vec![]);
+    };
+    let mounts = mounts
+        .iter()
+        .map(|mount| {
+            let field = |key: &str| {
+                mount
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            Mount {
+                kind: field("Type"),
+                source: field("Source"),
+                destination: field("Destination"),
+                read_only: mount.get("RW").and_then(|rw| rw.as_bool()) == Some(false),
+            }
+        })
+        .collect();
+    Ok(mounts)
+}
+
+/// Reads the ID of the image of the container from the output of `inspect --type container`
+pub(crate) fn parse_container_image_id(output: &str) -> anyhow::Result<String> {
+    let value = parse_inspected_container(output)?;
+    value
+        .get("Image")
+        .and_then(|image| image.as_str())
+        .filter(|image| !image.is_empty())
+        .map(|image| image.to_string())
+        .ok_or_else(|| anyhow::anyhow!("The container has no image"))
+}
+
+/// Reads the digest reference of the image from the output of `image inspect`.
+/// The image may have been pulled from several registries, the first one is used.
+pub(crate) fn parse_repo_digest(output: &str) -> anyhow::Result<String> {
+    let value = parse_inspected_container(output)?;
+    value
+        .get("RepoDigests")
+        .and_then(|digests| digests.as_array())
+        .and_then(|digests| digests.first())
+        .and_then(|digest| digest.as_str())
+        .map(|digest| digest.to_string())
+        .ok_or_else(|| anyhow::anyhow!("The image has no digest, it was probably built locally"))
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -51,3 +293,166 @@ pub async fn get_container_runtime(
         Some(Rc::new(podman) as Rc<dyn ContainerRuntime>)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_container_list_classifies_podman_output() -> anyhow::Result<()> {
+        let output = r#"[
+            {"Id": "d24405b14180", "Names": ["ubuntu"], "Image": "docker.io/library/ubuntu:latest", "State": "running", "Labels": {"manager": "distrobox"}},
+            {"Id": "a1b2c3d4e5f6", "Names": ["postgres"], "Image": "docker.io/library/postgres:16", "State": "exited", "Labels": {"maintainer": "someone"}},
+            {"Id": "0f9e8d7c6b5a", "Names": ["scratch"], "Image": "docker.io/library/alpine:latest", "State": "created", "Labels": null}
+        ]"#;
+        let containers = parse_container_list(output)?;
+
+        let classified: Vec<(&str, bool)> = containers
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_distrobox))
+            .collect();
+        assert_eq!(
+            classified,
+            [("ubuntu", true), ("postgres", false), ("scratch", false)]
+        );
+        assert_eq!(containers[1].id, "a1b2c3d4e5f6");
+        assert_eq!(containers[1].image, "docker.io/library/postgres:16");
+        assert_eq!(containers[1].state, "exited");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_container_list_classifies_docker_output() -> anyhow::Result<()> {
+        let output = r#"{"ID":"d24405b14180","Names":"fedora","Image":"registry.fedoraproject.org/fedora-toolbox:40","State":"running","Labels":"com.github.containers.toolbox=true,manager=distrobox"}
+{"ID":"a1b2c3d4e5f6","Names":"web","Image":"nginx:latest","State":"running","Labels":""}"#;
+        let containers = parse_container_list(output)?;
+
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].name, "fedora");
+        assert!(containers[0].is_distrobox);
+        assert_eq!(containers[1].name, "web");
+        assert!(!containers[1].is_distrobox);
+
+        assert!(parse_container_list("")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_network_info_reads_addresses_and_ports() -> anyhow::Result<()> {
+        let output = r#"[{
+            "Id": "d24405b14180",
+            "HostConfig": {"NetworkMode": "bridge"},
+            "NetworkSettings": {
+                "IPAddress": "10.88.0.5",
+                "Ports": {
+                    "22/tcp": [{"HostIp": "0.0.0.0", "HostPort": "2222"}],
+                    "8080/tcp": null
+                },
+                "Networks": {"podman": {"IPAddress": "10.88.0.5"}, "extra": {"IPAddress": "10.89.0.3"}}
+            }
+        }]"#;
+        let info = parse_network_info(output)?;
+
+        assert!(!info.host_network);
+        assert_eq!(info.ip_addresses, ["10.88.0.5", "10.89.0.3"]);
+        assert_eq!(
+            info.ports,
+            [PortMapping {
+                container_port: "22/tcp".into(),
+                host_ip: "0.0.0.0".into(),
+                host_port: "2222".into(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_network_info_handles_host_network() -> anyhow::Result<()> {
+        let output = r#"[{
+            "HostConfig": {"NetworkMode": "host"},
+            "NetworkSettings": {"IPAddress": "", "Ports": {}, "Networks": {"host": {"IPAddress": ""}}}
+        }]"#;
+        let info = parse_network_info(output)?;
+
+        assert_eq!(
+            info,
+            NetworkInfo {
+                host_network: true,
+                ..Default::default()
+            }
+        );
+        assert!(parse_network_info("[]").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_mounts_reads_binds_and_volumes() -> anyhow::Result<()> {
+        let output = r#"[{
+            "Mounts": [
+                {"Type": "bind", "Source": "/home/me", "Destination": "/home/me", "RW": true},
+                {"Type": "bind", "Source": "/", "Destination": "/run/host", "Mode": "", "RW": false},
+                {"Type": "volume", "Name": "cache", "Source": "/var/lib/containers/storage/volumes/cache/_data", "Destination": "/cache", "RW": true}
+            ]
+        }]"#;
+        let mounts = parse_mounts(output)?;
+
+        assert_eq!(
+            mounts,
+            [
+                Mount {
+                    kind: "bind".into(),
+                    source: "/home/me".into(),
+                    destination: "/home/me".into(),
+                    read_only: false,
+                },
+                Mount {
+                    kind: "bind".into(),
+                    source: "/".into(),
+                    destination: "/run/host".into(),
+                    read_only: true,
+                },
+                Mount {
+                    kind: "volume".into(),
+                    source: "/var/lib/containers/storage/volumes/cache/_data".into(),
+                    destination: "/cache".into(),
+                    read_only: false,
+                },
+            ]
+        );
+        assert!(parse_mounts(r#"[{"Id": "d24405b14180"}]"#)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_container_image_id_reads_the_image() -> anyhow::Result<()> {
+        let output = r#"[{
+            "Id": "d24405b14180",
+            "Image": "sha256:b1d9df8ab81559494794e522b380878cf9ba82d4c1fb67293bcf931c3aa69ae4",
+            "ImageName": "docker.io/library/ubuntu:latest"
+        }]"#;
+        assert_eq!(
+            parse_container_image_id(output)?,
+            "sha256:b1d9df8ab81559494794e522b380878cf9ba82d4c1fb67293bcf931c3aa69ae4"
+        );
+        assert!(parse_container_image_id(r#"[{"Id": "d24405b14180"}]"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_repo_digest_reads_first_digest() -> anyhow::Result<()> {
+        let output = r#"[{
+            "Id": "b1d9df8ab81559494794e522b380878cf9ba82d4c1fb67293bcf931c3aa69ae4",
+            "RepoTags": ["docker.io/library/ubuntu:latest"],
+            "RepoDigests": [
+                "docker.io/library/ubuntu@sha256:2e863c44b718727c860746568e1d54afd13b2fa71b160f5cd9058fc436217b30",
+                "docker.io/library/ubuntu@sha256:6015f66923d7afbc53558d7ccffd325d43b4e249f41a6e93eef074c9505d2233"
+            ]
+        }]"#;
+        assert_eq!(
+            parse_repo_digest(output)?,
+            "docker.io/library/ubuntu@sha256:2e863c44b718727c860746568e1d54afd13b2fa71b160f5cd9058fc436217b30"
+        );
+        assert!(parse_repo_digest(r#"[{"RepoDigests": []}]"#).is_err());
+        Ok(())
+    }
+}
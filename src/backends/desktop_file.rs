@@ -1,8 +1,74 @@
+use std::ffi::OsStr;
+
+use crate::fakers::shell_quote;
+
 #[derive(Debug, Default, Clone)]
 pub struct DesktopEntry {
     pub name: String,
     pub exec: String,
     pub icon: String,
+    /// The `Categories=` key, e.g. `["Development", "IDE"]`
+    pub categories: Vec<String>,
+}
+
+/// The main categories of the Desktop Menu spec. Every app should list at least one of them
+/// in its categories, the rest being additional categories like "IDE". "Audio" and "Video" are
+/// left out, as they come with "AudioVideo"
+const MAIN_CATEGORIES: &[&str] = &[
+    "AudioVideo",
+    "Development",
+    "Education",
+    "Game",
+    "Graphics",
+    "Network",
+    "Office",
+    "Science",
+    "Settings",
+    "System",
+    "Utility",
+];
+
+impl DesktopEntry {
+    /// The first main category of the app, used to group it with similar apps.
+    /// "Audio" and "Video" are reported as "AudioVideo", in case it comes after them
+    pub fn primary_category(&self) -> Option<&str> {
+        self.categories
+            .iter()
+            .map(|category| match category.as_str() {
+                "Audio" | "Video" => "AudioVideo",
+                category => category,
+            })
+            .find(|category| MAIN_CATEGORIES.contains(category))
+    }
+
+    /// Expands the field codes of the Exec key, as defined by the Desktop Entry spec.
+    /// We never pass files or URLs, so `%f`, `%F`, `%u` and `%U` are removed, together with
+    /// the deprecated and unknown codes.
+    pub fn expanded_exec(&self, desktop_file_path: &str) -> String {
+        let quote = |value: &str| shell_quote(OsStr::new(value));
+        let mut expanded = String::with_capacity(self.exec.len());
+        let mut chars = self.exec.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                expanded.push(c);
+                continue;
+            }
+            let replacement = match chars.next() {
+                Some('%') => "%".to_string(),
+                Some('i') if !self.icon.is_empty() => format!("--icon {}", quote(&self.icon)),
+                Some('c') => quote(&self.name),
+                Some('k') => quote(desktop_file_path),
+                _ => String::new(),
+            };
+            // Drop the separator of arguments that expand to nothing
+            let at_arg_end = chars.peek().is_none_or(|c| c.is_whitespace());
+            if replacement.is_empty() && at_arg_end && expanded.ends_with(' ') {
+                expanded.pop();
+            }
+            expanded.push_str(&replacement);
+        }
+        expanded
+    }
 }
 
 /// Extracts the first string enclosed in the specified quote character from a line of text.
@@ -25,6 +91,7 @@ pub fn parse_desktop_file(content: &str) -> anyhow::Result<DesktopEntry> {
     let mut name = None;
     let mut exec = None;
     let mut icon = None;
+    let mut categories = None;
     let mut in_desktop_entry = false;
 
     for line in content.lines() {
@@ -45,11 +112,21 @@ pub fn parse_desktop_file(content: &str) -> anyhow::Result<DesktopEntry> {
                 "Name" => name = Some(value.trim().to_string()),
                 "Exec" => exec = Some(value.trim().to_string()),
                 "Icon" => icon = Some(value.trim().to_string()),
+                "Categories" => {
+                    categories = Some(
+                        value
+                            .split(';')
+                            .map(str::trim)
+                            .filter(|category| !category.is_empty())
+                            .map(String::from)
+                            .collect(),
+                    )
+                }
                 _ => {}
             }
         }
 
-        if name.is_some() && exec.is_some() && icon.is_some() {
+        if name.is_some() && exec.is_some() && icon.is_some() && categories.is_some() {
             break; // Exit early if we have all required fields
         }
     }
@@ -57,8 +134,14 @@ pub fn parse_desktop_file(content: &str) -> anyhow::Result<DesktopEntry> {
     let name = name.ok_or_else(|| anyhow::anyhow!("Missing Name key"))?;
     let exec = exec.ok_or_else(|| anyhow::anyhow!("Missing Exec key"))?;
     let icon = icon.unwrap_or_default();
+    let categories = categories.unwrap_or_default();
 
-    Ok(DesktopEntry { name, icon, exec })
+    Ok(DesktopEntry {
+        name,
+        icon,
+        exec,
+        categories,
+    })
 }
 
 #[cfg(test)]
@@ -135,6 +218,56 @@ Icon=test-icon
         assert_eq!(&entry.exec, "/usr/bin/test --param=value");
     }
 
+    #[test]
+    fn test_categories() {
+        let content = r#"
+[Desktop Entry]
+Name=Builder
+Exec=gnome-builder
+Categories=GNOME;GTK;IDE;Development;
+        "#;
+        let entry = parse_desktop_file(content).unwrap();
+        assert_eq!(entry.categories, ["GNOME", "GTK", "IDE", "Development"]);
+        assert_eq!(entry.primary_category(), Some("Development"));
+
+        let content = "[Desktop Entry]\nName=Player\nExec=player\nCategories=Video;AudioVideo;";
+        let entry = parse_desktop_file(content).unwrap();
+        assert_eq!(entry.primary_category(), Some("AudioVideo"));
+
+        let content = "[Desktop Entry]\nName=Tool\nExec=tool\nCategories=X-Custom;";
+        let entry = parse_desktop_file(content).unwrap();
+        assert_eq!(entry.primary_category(), None);
+    }
+
+    #[test]
+    fn test_expanded_exec() {
+        let entry = |exec: &str, icon: &str| DesktopEntry {
+            name: "Text Editor".into(),
+            exec: exec.into(),
+            icon: icon.into(),
+            ..Default::default()
+        };
+        let path = "/usr/share/applications/org.gnome.TextEditor.desktop";
+
+        assert_eq!(
+            entry("gnome-text-editor %U", "text-editor").expanded_exec(path),
+            "gnome-text-editor"
+        );
+        assert_eq!(
+            entry("editor %i --title %c %F", "text-editor").expanded_exec(path),
+            "editor --icon text-editor --title 'Text Editor'"
+        );
+        // Without an icon, %i expands to nothing
+        assert_eq!(
+            entry("editor %i --new-window", "").expanded_exec(path),
+            "editor --new-window"
+        );
+        assert_eq!(
+            entry("editor --desktop-file=%k --progress=50%% %d", "").expanded_exec(path),
+            "editor --desktop-file=/usr/share/applications/org.gnome.TextEditor.desktop --progress=50%"
+        );
+    }
+
     #[test]
     fn test_extract_quoted_string_single_quotes() {
         let line = "exec '/usr/bin/vim' \"$@\"";
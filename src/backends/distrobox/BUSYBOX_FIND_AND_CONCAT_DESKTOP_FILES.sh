@@ -0,0 +1,36 @@
+set -eu
+
+# Same output as POSIX_FIND_AND_CONCAT_DESKTOP_FILES.sh, for containers without GNU grep/find
+# (e.g. alpine with busybox): the files are filtered in a loop instead of with `find -not -exec`.
+
+base16() (
+  set -eu;
+  if [ "$#" -eq 0 ]; then
+    cat
+  else
+    printf '%s' "$1"
+  fi | od -vt x1 -A n | tr -d ' \n\t'
+)
+
+dumpDesktopFiles() {
+  if ! [ -d "$1" ]; then
+    return
+  fi
+
+  find "$1" -name '*.desktop' | while IFS= read -r file; do
+    if grep -q '^[[:space:]]*NoDisplay[[:space:]]*=[[:space:]]*true[[:space:]]*$' "$file"; then
+      continue
+    fi
+    printf '"%s"="%s"\n' "$(base16 "$file")" "$(base16 <"$file")"
+  done
+}
+
+printf 'home_dir="%s"\n' "$(base16 "$HOME")"
+
+printf '[system]\n'
+dumpDesktopFiles /usr/share/applications
+
+printf '[user]\n'
+dumpDesktopFiles "$HOME/.local/share/applications"
+
+set +eu
@@ -0,0 +1,244 @@
+// Client-side checks of distrobox assemble manifests.
+// The manifest is an INI file with a section per container, see `man distrobox-assemble`.
+
+use std::fmt;
+
+const KNOWN_KEYS: &[&str] = &[
+    "additional_flags",
+    "additional_packages",
+    "clone",
+    "entry",
+    "exported_apps",
+    "exported_bins",
+    "exported_bins_path",
+    "home",
+    "hostname",
+    "image",
+    "include",
+    "init",
+    "init_hooks",
+    "nvidia",
+    "pre_init_hooks",
+    "pull",
+    "replace",
+    "root",
+    "start_now",
+    "unshare_all",
+    "unshare_devsys",
+    "unshare_groups",
+    "unshare_ipc",
+    "unshare_netns",
+    "unshare_process",
+    "volume",
+];
+
+const BOOLEAN_KEYS: &[&str] = &[
+    "entry",
+    "init",
+    "nvidia",
+    "pull",
+    "replace",
+    "root",
+    "start_now",
+    "unshare_all",
+    "unshare_devsys",
+    "unshare_groups",
+    "unshare_ipc",
+    "unshare_netns",
+    "unshare_process",
+];
+
+/// A structural error of an assemble manifest, with the line it was found on
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssembleError {
+    /// 1-based line number
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.text.is_empty() {
+            write!(f, "Line {}: {}", self.line, self.message)
+        } else {
+            write!(f, "Line {}: {} ({})", self.line, self.message, self.text)
+        }
+    }
+}
+
+struct Section<'a> {
+    name: &'a str,
+    line: usize,
+    text: &'a str,
+    keys: Vec<&'a str>,
+    includes: Vec<(usize, &'a str, &'a str)>,
+}
+
+/// Checks the structure of an assemble manifest, returning every error found
+pub fn validate_assemble(content: &str) -> Vec<AssembleError> {
+    let mut errors = vec![];
+    let mut sections: Vec<Section> = vec![];
+    let error = |line: usize, text: &str, message: String| AssembleError {
+        line,
+        text: text.to_string(),
+        message,
+    };
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line = index + 1;
+        let text = raw_line.trim();
+        if text.is_empty() || text.starts_with('#') || text.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = text.strip_prefix('[') {
+            match name.strip_suffix(']').map(str::trim) {
+                Some(name) if !name.is_empty() => {
+                    if sections.iter().any(|s| s.name == name) {
+                        let message = format!("Duplicate container \"{name}\"");
+                        errors.push(error(line, text, message));
+                    }
+                    sections.push(Section {
+                        name,
+                        line,
+                        text,
+                        keys: vec![],
+                        includes: vec![],
+                    });
+                }
+                _ => {
+                    let message = "Invalid container header".to_string();
+                    errors.push(error(line, text, message));
+                }
+            }
+            continue;
+        }
+
+        let Some((key, value)) = text.split_once('=') else {
+            errors.push(error(line, text, "Expected a key=value pair".to_string()));
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim().trim_matches('"');
+        let Some(section) = sections.last_mut() else {
+            errors.push(error(
+                line,
+                text,
+                "Option outside of a [container] section".to_string(),
+            ));
+            continue;
+        };
+        if !KNOWN_KEYS.contains(&key) {
+            errors.push(error(line, text, format!("Unknown key \"{key}\"")));
+            continue;
+        }
+        if BOOLEAN_KEYS.contains(&key) && value != "true" && value != "false" {
+            errors.push(error(
+                line,
+                text,
+                format!("\"{key}\" must be true or false"),
+            ));
+        }
+        if key == "include" {
+            section.includes.push((line, text, value));
+        }
+        section.keys.push(key);
+    }
+
+    if sections.is_empty() {
+        errors.push(error(1, "", "No container defined".to_string()));
+    }
+    for section in &sections {
+        for (line, text, included) in &section.includes {
+            if !sections.iter().any(|s| s.name == *included) {
+                errors.push(error(
+                    *line,
+                    text,
+                    format!("Included container \"{included}\" is not defined"),
+                ));
+            }
+        }
+        // Included containers provide the image, and clones take it from their source
+        if !["image", "include", "clone"]
+            .iter()
+            .any(|key| section.keys.contains(key))
+        {
+            errors.push(error(
+                section.line,
+                section.text,
+                format!("Container \"{}\" has no image", section.name),
+            ));
+        }
+    }
+    errors.sort_by_key(|e| e.line);
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_manifest_passes() {
+        let manifest = "\
+# Development containers
+[base]
+image=registry.fedoraproject.org/fedora-toolbox:40
+additional_packages=\"git vim\"
+additional_packages=htop
+init=false
+
+[dev]
+include=base
+volume=/srv/data:/data:ro
+nvidia=true
+start_now=true
+";
+        assert_eq!(validate_assemble(manifest), []);
+    }
+
+    #[test]
+    fn malformed_manifests_report_errors() {
+        let messages = |manifest: &str| -> Vec<(usize, String)> {
+            validate_assemble(manifest)
+                .into_iter()
+                .map(|e| (e.line, e.message))
+                .collect()
+        };
+
+        assert_eq!(
+            messages("[box]\ninit=true\n"),
+            [(1, "Container \"box\" has no image".to_string())]
+        );
+        assert_eq!(
+            messages("[box]\nimage=alpine\nimgae=fedora\n"),
+            [(3, "Unknown key \"imgae\"".to_string())]
+        );
+        assert_eq!(
+            messages("image=alpine\n[box]\nimage=alpine\nroot=yes\n"),
+            [
+                (1, "Option outside of a [container] section".to_string()),
+                (4, "\"root\" must be true or false".to_string()),
+            ]
+        );
+        assert_eq!(
+            messages("[box\nimage=alpine\n"),
+            [
+                (1, "Invalid container header".to_string()),
+                (1, "No container defined".to_string()),
+                (2, "Option outside of a [container] section".to_string()),
+            ]
+        );
+        assert_eq!(
+            messages("[box]\ninclude=missing\nfoo\n"),
+            [
+                (
+                    2,
+                    "Included container \"missing\" is not defined".to_string()
+                ),
+                (3, "Expected a key=value pair".to_string()),
+            ]
+        );
+    }
+}
@@ -1,10 +1,10 @@
-use crate::fakers::{Child, Command, CommandRunner, FdMode, NullCommandRunnerBuilder};
+use crate::fakers::{Child, Command, CommandRunner, FdMode, NullCommandRunnerBuilder, shell_quote};
 
 use serde::{Deserialize, Deserializer};
 use std::{
-    cell::LazyCell,
+    cell::{LazyCell, RefCell},
     collections::{BTreeMap, HashMap},
-    ffi::OsString,
+    ffi::{OsStr, OsString},
     io,
     os::unix::ffi::OsStringExt,
     path::{Path, PathBuf},
@@ -14,11 +14,17 @@ use std::{
 };
 use tracing::{debug, error, info, warn};
 
+use crate::backends::container_runtime::Mount;
 use crate::backends::desktop_file::*;
 use crate::backends::distrobox::command::{CmdFactory, default_cmd_factory};
 
 const POSIX_FIND_AND_CONCAT_DESKTOP_FILES: &str =
     include_str!("POSIX_FIND_AND_CONCAT_DESKTOP_FILES.sh");
+const BUSYBOX_FIND_AND_CONCAT_DESKTOP_FILES: &str =
+    include_str!("BUSYBOX_FIND_AND_CONCAT_DESKTOP_FILES.sh");
+// busybox grep doesn't know --version and fails printing its usage instead
+const GREP_PROBE: &str =
+    "if command -v grep >/dev/null 2>&1; then grep --version 2>&1 || true; else echo no-grep; fi";
 
 /// Encode a string as hex (matching the shell script's base16 function)
 fn to_hex(s: &str) -> String {
@@ -102,6 +108,8 @@ impl DesktopFiles {
 pub struct Distrobox {
     cmd_runner: CommandRunner,
     cmd_factory: CmdFactory,
+    /// The grep of each container, probed once since it takes an extra `enter`
+    grep_flavors: Rc<RefCell<HashMap<String, GrepFlavor>>>,
 }
 
 type CommandResponse = (Command, Rc<dyn Fn() -> io::Result<String>>);
@@ -209,6 +217,50 @@ pub struct ExportableApp {
     pub exported: bool,
 }
 
+/// Apps sharing the same primary category, exported together from the exports dialog
+#[derive(Debug, Clone)]
+pub struct AppGroup {
+    /// None for apps without any main category
+    pub category: Option<String>,
+    pub apps: Vec<ExportableApp>,
+}
+
+impl AppGroup {
+    /// Desktop file paths of the apps of the group that aren't exported yet
+    pub fn unexported_paths(&self) -> Vec<String> {
+        self.apps
+            .iter()
+            .filter(|app| !app.exported)
+            .map(|app| app.desktop_file_path.clone())
+            .collect()
+    }
+}
+
+/// Groups the apps by their primary category, sorted by category name with the uncategorized
+/// apps last. Apps keep their relative order inside each group.
+pub fn group_apps_by_category(apps: impl IntoIterator<Item = ExportableApp>) -> Vec<AppGroup> {
+    let mut groups: Vec<AppGroup> = vec![];
+    for app in apps {
+        let category = app.entry.primary_category().map(String::from);
+        match groups.iter_mut().find(|group| group.category == category) {
+            Some(group) => group.apps.push(app),
+            None => groups.push(AppGroup {
+                category,
+                apps: vec![app],
+            }),
+        }
+    }
+    groups.sort_by_key(|group| (group.category.is_none(), group.category.clone()));
+    groups
+}
+
+/// An exported desktop file on the host whose source desktop file no longer exists in the container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleExport {
+    pub file_name: String,
+    pub host_path: PathBuf,
+}
+
 #[derive(Debug, Clone)]
 pub struct ExportableBinary {
     pub name: String,
@@ -263,6 +315,136 @@ impl std::fmt::Display for CreateArgsImage {
     }
 }
 
+fn validate_timezone(timezone: &str) -> Result<(), InvalidValue> {
+    let is_valid = !timezone.is_empty()
+        && !timezone.starts_with('/')
+        && !timezone
+            .split('/')
+            .any(|part| part.is_empty() || part == "..")
+        && timezone
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/_+-".contains(c));
+    if is_valid {
+        Ok(())
+    } else {
+        Err(InvalidValue {
+            hint: format!("Invalid timezone: {timezone}"),
+        })
+    }
+}
+
+/// Parses environment variable assignments separated by spaces, e.g. "GTK_DEBUG=interactive LANG=C"
+pub fn parse_env_assignments(text: &str) -> Result<Vec<(String, String)>, InvalidValue> {
+    text.split_whitespace()
+        .map(|assignment| {
+            let (name, value) = assignment.split_once('=').ok_or_else(|| InvalidValue {
+                hint: format!("Expected NAME=value, got {assignment}"),
+            })?;
+            validate_env_name(name)?;
+            Ok((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn validate_env_name(name: &str) -> Result<(), InvalidValue> {
+    let is_valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_valid {
+        Ok(())
+    } else {
+        Err(InvalidValue {
+            hint: format!("Invalid environment variable name: {name}"),
+        })
+    }
+}
+
+/// The grep found in a container, deciding how its desktop files are discovered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrepFlavor {
+    Gnu,
+    /// Anything else, usually the busybox applet of musl based images
+    Busybox,
+    /// No grep at all, which both scripts need
+    Missing,
+}
+
+impl GrepFlavor {
+    /// An inconclusive probe keeps the GNU script, which works on most images
+    fn from_probe(output: &str) -> Self {
+        if output.trim() == "no-grep" {
+            Self::Missing
+        } else if output.trim().is_empty() || output.contains("GNU grep") {
+            Self::Gnu
+        } else {
+            Self::Busybox
+        }
+    }
+
+    fn desktop_files_script(&self) -> Result<&'static str, Error> {
+        match self {
+            Self::Gnu => Ok(POSIX_FIND_AND_CONCAT_DESKTOP_FILES),
+            Self::Busybox => Ok(BUSYBOX_FIND_AND_CONCAT_DESKTOP_FILES),
+            Self::Missing => Err(Error::MissingGrep),
+        }
+    }
+}
+
+// distrobox-init creates this file once the container setup, hooks included, succeeds.
+// It's read with an exec of the container manager, picked like distrobox does: `distrobox enter`
+// itself waits for the setup, and fails when it didn't complete.
+pub(crate) const SETUP_DONE_CHECK: &str = concat!(
+    r#"manager="${DBX_CONTAINER_MANAGER:-}"; "#,
+    r#"if [ -z "$manager" ]; then "#,
+    r#"if command -v podman >/dev/null 2>&1; then manager=podman; else manager=docker; fi; "#,
+    r#"fi; "#,
+    r#""$manager" exec "$1" test -e /run/.containersetupdone && echo done || echo missing"#
+);
+
+/// A container manager distrobox can drive, selected through `DBX_CONTAINER_MANAGER`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerManager {
+    Podman,
+    Docker,
+}
+
+impl ContainerManager {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "podman" => Some(Self::Podman),
+            "docker" => Some(Self::Docker),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Podman => "podman",
+            Self::Docker => "docker",
+        }
+    }
+}
+
+/// Where an app launched from the app is run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchSession {
+    /// Inside the container, sharing it with the other running apps
+    Shared,
+    /// Inside the container too, but with a D-Bus session bus of its own, so that the app
+    /// starts a new instance instead of handing over to one already running.
+    /// No `distrobox ephemeral --clone` here: distrobox refuses to clone a running container,
+    /// and every launch would commit the whole container to a new image.
+    Dedicated,
+}
+
+// Runs the command given as $1 with a private session bus, when the container has dbus-run-session
+const DEDICATED_SESSION_SCRIPT: &str = "if command -v dbus-run-session >/dev/null 2>&1; then exec dbus-run-session -- sh -c \"$1\"; fi; exec sh -c \"$1\"";
+
+const DBUS_RUN_SESSION_CHECK: &str =
+    "command -v dbus-run-session >/dev/null 2>&1 && echo found || echo missing";
+
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct CreateArgs {
     pub init: bool,
@@ -274,6 +456,10 @@ pub struct CreateArgs {
     pub image: Option<CreateArgsImage>,
     pub name: CreateArgName,
     pub volumes: Vec<Volume>,
+    /// Extra flags given to the container manager when creating the container
+    pub additional_flags: Vec<String>,
+    /// Overrides the container manager detected by distrobox
+    pub container_manager: Option<ContainerManager>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -289,51 +475,133 @@ impl std::fmt::Display for VolumeMode {
     }
 }
 
+/// How the SELinux label of the host path is changed to let the container use it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeRelabel {
+    /// `z`: the path can be shared with other containers
+    Shared,
+    /// `Z`: the path is reserved to this container
+    Private,
+}
+
+impl std::fmt::Display for VolumeRelabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VolumeRelabel::Shared => write!(f, "z"),
+            VolumeRelabel::Private => write!(f, "Z"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Volume {
     pub host_path: String,
     pub container_path: String,
     pub mode: Option<VolumeMode>,
+    pub relabel: Option<VolumeRelabel>,
+}
+
+impl Volume {
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.mode = read_only.then_some(VolumeMode::ReadOnly);
+        self
+    }
+
+    pub fn with_relabel(mut self, relabel: Option<VolumeRelabel>) -> Self {
+        self.relabel = relabel;
+        self
+    }
 }
 
 impl FromStr for Volume {
     type Err = InvalidValue;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidValue {
+            hint: format!("Invalid volume descriptor: {}", s),
+        };
         let parts: Vec<&str> = s.split(':').collect();
-        match parts.as_slice() {
-            [host] => Ok(Volume {
-                host_path: host.to_string(),
-                container_path: host.to_string(),
-                mode: None,
-            }),
-            [host, target] => Ok(Volume {
-                host_path: host.to_string(),
-                container_path: target.to_string(),
-                mode: None,
-            }),
-            [host, target, "ro"] => Ok(Volume {
-                host_path: host.to_string(),
-                container_path: target.to_string(),
-                mode: Some(VolumeMode::ReadOnly),
-            }),
-            _ => Err(InvalidValue {
-                hint: format!("Invalid volume descriptor: {}", s),
-            }),
+        let (host, target, options) = match parts.as_slice() {
+            [host] => (host, host, None),
+            [host, target] => (host, target, None),
+            [host, target, options] => (host, target, Some(options)),
+            _ => return Err(invalid()),
+        };
+        let mut volume = Volume {
+            host_path: host.to_string(),
+            container_path: target.to_string(),
+            mode: None,
+            relabel: None,
+        };
+        // Options are comma separated, like "ro,z"
+        for option in options.iter().flat_map(|options| options.split(',')) {
+            match option {
+                "ro" => volume.mode = Some(VolumeMode::ReadOnly),
+                "rw" => volume.mode = None,
+                "z" => volume.relabel = Some(VolumeRelabel::Shared),
+                "Z" => volume.relabel = Some(VolumeRelabel::Private),
+                _ => return Err(invalid()),
+            }
         }
+        Ok(volume)
     }
 }
 
 impl std::fmt::Display for Volume {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}:{}", self.host_path, self.container_path)?;
-        if let Some(mode) = &self.mode {
-            write!(f, ":{}", mode)?;
+        let options: Vec<String> = [
+            self.mode.as_ref().map(|mode| mode.to_string()),
+            self.relabel.map(|relabel| relabel.to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !options.is_empty() {
+            write!(f, ":{}", options.join(","))?;
         }
         Ok(())
     }
 }
 
+// Mounted by `distrobox create` in every container, the new container gets them anyway
+const DISTROBOX_MOUNTS: &[&str] = &[
+    "/tmp",
+    "/dev",
+    "/sys",
+    "/media",
+    "/mnt",
+    "/var/mnt",
+    "/etc/hosts",
+    "/etc/hostname",
+    "/etc/resolv.conf",
+    "/etc/localtime",
+    "/var/log/journal",
+];
+const DISTROBOX_MOUNT_PREFIXES: &[&str] = &["/dev/", "/sys/", "/run/", "/usr/bin/"];
+
+/// Extracts the volumes added by the user from the mounts of a container, so that they can be
+/// given to a new one. The home folder and the mounts distrobox adds itself are skipped,
+/// as are named volumes, which only live in the container manager.
+pub fn user_volumes(mounts: &[Mount], home: &str) -> Vec<Volume> {
+    mounts
+        .iter()
+        .filter(|mount| mount.kind == "bind" && mount.destination != home)
+        .filter(|mount| {
+            !DISTROBOX_MOUNTS.contains(&mount.destination.as_str())
+                && !DISTROBOX_MOUNT_PREFIXES
+                    .iter()
+                    .any(|prefix| mount.destination.starts_with(prefix))
+        })
+        .map(|mount| Volume {
+            host_path: mount.source.clone(),
+            container_path: mount.destination.clone(),
+            mode: mount.read_only.then_some(VolumeMode::ReadOnly),
+            relabel: None,
+        })
+        .collect()
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("failed to read command stdout: {0}")]
@@ -357,6 +625,9 @@ pub enum Error {
 
     #[error("failed to resolve host path: {0}. getfattr may not be installed on the host")]
     ResolveHostPath(String),
+
+    #[error("grep isn't installed in the container, it's needed to find its apps")]
+    MissingGrep,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -504,16 +775,10 @@ impl DistroboxCommandRunnerResponse {
     ) -> Vec<(Command, String)> {
         let mut commands = Vec::new();
 
-        // Get XDG_DATA_HOME (mocked via printenv)
-        commands.push((
-            Command::new_with_args("printenv", ["XDG_DATA_HOME"]),
-            String::new(),
-        ));
-
-        // Get HOME if XDG_DATA_HOME is empty (mocked via printenv)
+        // Host environment (mocked via env -0), XDG_DATA_HOME is unset so HOME is used
         commands.push((
-            Command::new_with_args("printenv", ["HOME"]),
-            "/home/me".to_string(),
+            Command::new_with_args("env", ["-0"]),
+            "HOME=/home/me\0".to_string(),
         ));
 
         // List desktop files - these are the exported files in the user's local applications folder
@@ -597,6 +862,7 @@ impl Distrobox {
         Self {
             cmd_runner,
             cmd_factory,
+            grep_flavors: Default::default(),
         }
     }
 
@@ -734,11 +1000,37 @@ impl Distrobox {
         Ok(apps)
     }
 
+    async fn grep_flavor(&self, box_name: &str) -> GrepFlavor {
+        if let Some(flavor) = self.grep_flavors.borrow().get(box_name) {
+            return *flavor;
+        }
+        let mut cmd = self.dbcmd();
+        cmd.args(["enter", box_name, "--", "sh", "-c", GREP_PROBE]);
+        match self.cmd_output_string(cmd).await {
+            Ok(output) => {
+                let flavor = GrepFlavor::from_probe(&output);
+                // grep may be installed later, so a missing one is probed again
+                if flavor != GrepFlavor::Missing {
+                    self.grep_flavors
+                        .borrow_mut()
+                        .insert(box_name.to_string(), flavor);
+                }
+                flavor
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to probe grep, assuming GNU grep");
+                GrepFlavor::Gnu
+            }
+        }
+    }
+
     async fn get_desktop_files(
         &self,
         box_name: &str,
         host_env: &HashMap<String, String>,
     ) -> Result<Vec<(String, String)>, Error> {
+        let flavor = self.grep_flavor(box_name).await;
+        debug!(?flavor, "Discovering desktop files");
         let mut cmd = self.dbcmd();
         cmd.args([
             "enter",
@@ -746,7 +1038,7 @@ impl Distrobox {
             "--",
             "sh",
             "-c",
-            POSIX_FIND_AND_CONCAT_DESKTOP_FILES,
+            flavor.desktop_files_script()?,
         ]);
         let desktop_files: DesktopFiles = toml::from_str(&self.cmd_output_string(cmd).await?)
             .map_err(|e| Error::ParseOutput(format!("{e:?}")))?;
@@ -761,14 +1053,18 @@ impl Distrobox {
             .collect::<Vec<_>>())
     }
 
-    pub async fn list_apps(&self, box_name: &str) -> Result<Vec<ExportableApp>, Error> {
-        let host_env = match crate::fakers::resolve_host_env(&self.cmd_runner).await {
+    async fn host_env(&self) -> HashMap<String, String> {
+        match crate::fakers::resolve_host_env(&self.cmd_runner).await {
             Ok(env) => env,
             Err(e) => {
                 tracing::warn!("failed to resolve host env via CommandRunner: {e:?}");
                 HashMap::new()
             }
-        };
+        }
+    }
+
+    pub async fn list_apps(&self, box_name: &str) -> Result<Vec<ExportableApp>, Error> {
+        let host_env = self.host_env().await;
 
         let files = self.get_desktop_files(box_name, &host_env).await?;
         debug!(desktop_files=?files);
@@ -806,6 +1102,98 @@ impl Distrobox {
         Ok(res)
     }
 
+    /// Lists the desktop files exported from `box_name` whose source desktop file was removed
+    /// from the container, e.g. because the app was uninstalled.
+    pub async fn list_stale_exports(&self, box_name: &str) -> Result<Vec<StaleExport>, Error> {
+        let host_env = self.host_env().await;
+
+        let files = self.get_desktop_files(box_name, &host_env).await?;
+        let exported = self.get_exported_desktop_files(&host_env).await?;
+        // Exports of other containers may share our prefix (e.g. "ubuntu" and "ubuntu-dev")
+        let other_boxes: Vec<String> = self
+            .list()
+            .await?
+            .into_keys()
+            .filter(|name| name != box_name)
+            .collect();
+        let apps_path = self.host_applications_path(&host_env).await?;
+
+        let source_file_names: Vec<&str> = files
+            .iter()
+            .filter_map(|(path, _)| Path::new(path).file_name().and_then(|x| x.to_str()))
+            .collect();
+
+        Ok(
+            find_stale_exports(box_name, &exported, &source_file_names, &other_boxes)
+                .into_iter()
+                .map(|file_name| StaleExport {
+                    file_name: file_name.to_string(),
+                    host_path: apps_path.join(file_name),
+                })
+                .collect(),
+        )
+    }
+
+    /// Lists the desktop files exported by every container, as file names
+    /// in the host applications directory
+    pub async fn list_all_exports(&self) -> Result<Vec<String>, Error> {
+        let host_env = self.host_env().await;
+        let exported = self.get_exported_desktop_files(&host_env).await?;
+        let boxes: Vec<String> = self.list().await?.into_keys().collect();
+        Ok(exported
+            .into_iter()
+            .filter(|file_name| {
+                file_name.ends_with(".desktop")
+                    && boxes
+                        .iter()
+                        .any(|name| file_name.starts_with(&format!("{name}-")))
+            })
+            .collect())
+    }
+
+    /// Archives the desktop files exported by every container into a gzipped tarball,
+    /// returning how many were saved
+    pub async fn backup_exports(&self, archive: &Path) -> Result<usize, Error> {
+        let exports = self.list_all_exports().await?;
+        if exports.is_empty() {
+            return Err(InvalidValue {
+                hint: "there are no exported apps to back up".into(),
+            }
+            .into());
+        }
+        let apps_path = self.host_applications_path(&self.host_env().await).await?;
+        let mut cmd = Command::new("tar");
+        cmd.arg("-czf")
+            .arg(archive)
+            .arg("-C")
+            .arg(apps_path)
+            .arg("--")
+            .args(&exports);
+        self.cmd_output_string(cmd).await?;
+        Ok(exports.len())
+    }
+
+    /// Extracts the desktop files of a backup made by `backup_exports` into the host applications directory
+    pub async fn restore_exports(&self, archive: &Path) -> Result<String, Error> {
+        let apps_path = self.host_applications_path(&self.host_env().await).await?;
+        let mut cmd = Command::new("tar");
+        // Only desktop files are extracted, whatever else the archive contains
+        cmd.arg("-xzf").arg(archive).arg("-C").arg(apps_path).args([
+            "--no-same-owner",
+            "--wildcards",
+            "--no-anchored",
+            "*.desktop",
+        ]);
+        self.cmd_output_string(cmd).await
+    }
+
+    /// Removes an exported desktop file left behind by an uninstalled app
+    pub async fn remove_stale_export(&self, export: &StaleExport) -> Result<String, Error> {
+        let mut cmd = Command::new("rm");
+        cmd.arg("-f").arg("--").arg(&export.host_path);
+        self.cmd_output_string(cmd).await
+    }
+
     /// Lists only the binaries that have already been exported from the container.
     pub async fn get_exported_binaries(
         &self,
@@ -911,14 +1299,34 @@ impl Distrobox {
         &self,
         container: &str,
         app: &ExportableApp,
+        session: &LaunchSession,
+    ) -> Result<Box<dyn Child + Send>, Error> {
+        self.launch_app_with_env(container, app, session, &[])
+    }
+
+    /// Like `launch_app`, setting the `env` variables for this launch only
+    pub fn launch_app_with_env(
+        &self,
+        container: &str,
+        app: &ExportableApp,
+        session: &LaunchSession,
+        env: &[(String, String)],
     ) -> Result<Box<dyn Child + Send>, Error> {
         let mut cmd = self.dbcmd();
         cmd.arg("enter").arg("--name").arg(container).arg("--");
-        let to_be_replaced = [" %f", " %u", " %F", " %U"];
-        let cleaned_exec = to_be_replaced
-            .into_iter()
-            .fold(app.entry.exec.clone(), |acc, x| acc.replace(x, ""));
-        cmd.arg(cleaned_exec);
+        if *session == LaunchSession::Dedicated {
+            cmd.args(["sh", "-c", DEDICATED_SESSION_SCRIPT, "sh"]);
+        }
+        let mut exec = String::new();
+        if !env.is_empty() {
+            exec.push_str("env ");
+            for (name, value) in env {
+                validate_env_name(name)?;
+                exec.push_str(&format!("{name}={} ", shell_quote(OsStr::new(value))));
+            }
+        }
+        exec.push_str(&app.entry.expanded_exec(&app.desktop_file_path));
+        cmd.arg(exec);
         self.cmd_spawn(cmd)
     }
 
@@ -935,6 +1343,124 @@ impl Distrobox {
 
         self.cmd_output_string(cmd).await
     }
+
+    /// Checks if the host already has an app with the same desktop file name.
+    /// Reverse-DNS host entries (e.g. flatpak's "org.mozilla.firefox.desktop") are compared by their
+    /// last component, so they clash with "firefox.desktop" too.
+    pub async fn host_has_desktop(&self, desktop_file_name: &str) -> Result<bool, Error> {
+        let host_env = self.host_env().await;
+        let host_files = self.get_host_desktop_files(&host_env).await?;
+        Ok(host_files
+            .iter()
+            .any(|host_file| desktop_names_clash(desktop_file_name, host_file)))
+    }
+
+    /// Lists the desktop files in the host's XDG data directories, ignoring missing ones
+    async fn get_host_desktop_files(
+        &self,
+        host_env: &HashMap<String, String>,
+    ) -> Result<Vec<String>, Error> {
+        let mut dirs = vec![self.host_applications_path(host_env).await?];
+        let data_dirs = host_env
+            .get("XDG_DATA_DIRS")
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .unwrap_or("/usr/local/share:/usr/share");
+        dirs.extend(
+            data_dirs
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(|dir| Path::new(dir).join("applications")),
+        );
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(r#"for d in "$@"; do ls -1 "$d" 2>/dev/null; done; true"#)
+            .arg("sh")
+            .args(dirs);
+        let output = self.cmd_output_string(cmd).await?;
+        Ok(output
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| line.ends_with(".desktop"))
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    /// Changes the name shown in the app menu of an exported app, to tell it apart from a host app
+    pub async fn rename_exported_app(
+        &self,
+        box_name: &str,
+        desktop_file_name: &str,
+        new_name: &str,
+    ) -> Result<String, Error> {
+        let exported_path = self
+            .exported_desktop_path(box_name, desktop_file_name)
+            .await?;
+
+        let escaped_name: String = new_name
+            .chars()
+            .filter(|c| *c != '\n' && *c != '\r')
+            .flat_map(|c| match c {
+                '\\' | '/' | '&' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect();
+        // The localized names would still show up instead of the new one, so they are dropped
+        let mut cmd = Command::new("sed");
+        cmd.arg("-i")
+            .arg(format!(
+                r"/^\[Desktop Entry\]/,/^\[/ {{ /^Name\[/d; s/^Name=.*/Name={escaped_name}/; }}"
+            ))
+            .arg(exported_path);
+        self.cmd_output_string(cmd).await
+    }
+
+    /// Makes an exported app start in a dedicated session too, like `LaunchSession::Dedicated`.
+    /// Its Exec is left as is when the container has no dbus-run-session, returning false.
+    pub async fn export_dedicated_session(
+        &self,
+        box_name: &str,
+        desktop_file_name: &str,
+    ) -> Result<bool, Error> {
+        let mut check = self.dbcmd();
+        check.args(["enter", "--name", box_name]).extend(
+            "--",
+            &Command::new_with_args("sh", ["-c", DBUS_RUN_SESSION_CHECK]),
+        );
+        let output = self.cmd_output_string(check).await?;
+        if !output.lines().any(|line| line.trim() == "found") {
+            return Ok(false);
+        }
+
+        let exported_path = self
+            .exported_desktop_path(box_name, desktop_file_name)
+            .await?;
+        // distrobox-export writes `Exec=.../distrobox-enter [flags] -n <box> -- <command>`
+        let escaped_box = box_name.replace('.', r"\.");
+        let mut cmd = Command::new("sed");
+        cmd.arg("-i")
+            .arg(format!(
+                r"/^Exec=/ {{ /dbus-run-session/! s/\(-n \+{escaped_box} \+-- \+\)/\1dbus-run-session -- /; }}"
+            ))
+            .arg(exported_path);
+        self.cmd_output_string(cmd).await?;
+        Ok(true)
+    }
+
+    /// The desktop file distrobox-export writes on the host for an app of `box_name`
+    async fn exported_desktop_path(
+        &self,
+        box_name: &str,
+        desktop_file_name: &str,
+    ) -> Result<PathBuf, Error> {
+        let host_env = self.host_env().await;
+        Ok(self
+            .host_applications_path(&host_env)
+            .await?
+            .join(format!("{box_name}-{desktop_file_name}")))
+    }
+
     pub async fn unexport_app(
         &self,
         container: &str,
@@ -1067,6 +1593,20 @@ impl Distrobox {
         for volume in args.volumes {
             cmd.arg("--volume").arg(volume.to_string());
         }
+        if !args.additional_flags.is_empty() {
+            cmd.arg("--additional-flags")
+                .arg(args.additional_flags.join(" "));
+        }
+        if let Some(manager) = args.container_manager {
+            let mut env_cmd = Command::new("env");
+            env_cmd.arg(format!("DBX_CONTAINER_MANAGER={}", manager.as_str()));
+            env_cmd.arg(cmd.program);
+            env_cmd.args(cmd.args);
+            env_cmd.stdin = cmd.stdin;
+            env_cmd.stdout = cmd.stdout;
+            env_cmd.stderr = cmd.stderr;
+            cmd = env_cmd;
+        }
         cmd
     }
     // create
@@ -1097,15 +1637,65 @@ impl Distrobox {
         cmd.arg("enter").arg(name).arg("--no-workdir");
         cmd
     }
-    // clone from an existing container using create args to customize the clone
+    /// Like `enter_cmd`, running `command` through a shell instead of a plain interactive one
+    pub fn enter_cmd_with_command(&self, name: &str, command: Option<&str>) -> Command {
+        let mut cmd = self.enter_cmd(name);
+        if let Some(command) = command {
+            cmd.extend("--", &Command::new_with_args("sh", ["-c", command]));
+        }
+        cmd
+    }
+    // clone from an existing container using create args to customize the clone.
+    // `source_manager` is the manager holding the source container: when `args` targets
+    // a different one, the source is committed to an image, copied over and created from there,
+    // because `--clone` only works within a single manager. The intermediate image is removed
+    // once the container is created from it, or fails to be.
     pub async fn clone_from(
         &self,
         source_name: &str,
-        args: CreateArgs,
+        source_manager: Option<ContainerManager>,
+        mut args: CreateArgs,
     ) -> Result<Box<dyn Child + Send>, Error> {
-        let mut cmd = self.create_cmd(args);
-        cmd.remove_flag_value_arg("--image");
-        cmd.arg("--clone").arg(source_name);
+        let managers = match (source_manager, args.container_manager) {
+            (Some(source), Some(target)) if source != target => Some((source, target)),
+            _ => None,
+        };
+        let Some((source_manager, target_manager)) = managers else {
+            let mut cmd = self.create_cmd(args);
+            cmd.remove_flag_value_arg("--image");
+            cmd.arg("--clone").arg(source_name);
+            return self.cmd_spawn(cmd);
+        };
+
+        let image = format!("localhost/{}:clone", args.name.to_string().to_lowercase());
+        args.image = Some(CreateArgsImage::new(&image)?);
+        let create_cmd = self.create_cmd(args);
+
+        let mut cmd = Command::new("sh");
+        // The image goes through a file rather than a pipe: a failing `save` in a pipeline
+        // wouldn't stop the script
+        cmd.arg("-c").arg(concat!(
+            "set -e; ",
+            r#"archive=$(mktemp); trap 'rm -f "$archive"' EXIT; "#,
+            r#""$1" container commit "$2" "$3"; "#,
+            r#""$1" save -o "$archive" "$3"; "#,
+            r#""$4" load -i "$archive"; "#,
+            r#"rm -f "$archive"; "$1" image rm "$3"; "#,
+            r#"target=$4; image=$3; shift 4; set +e; "$@"; status=$?; "#,
+            // A created container keeps the layers of its image, so only its name can go.
+            // `podman image rm --force` would delete the container too, while docker just untags
+            r#"if [ "$status" -ne 0 ]; then "$target" image rm "$image"; "#,
+            r#"elif [ "$target" = podman ]; then "$target" untag "$image"; "#,
+            r#"else "$target" image rm --force "$image"; fi; "#,
+            r#"exit "$status""#
+        ));
+        cmd.arg("sh")
+            .arg(source_manager.as_str())
+            .arg(source_name)
+            .arg(&image)
+            .arg(target_manager.as_str());
+        cmd.arg(create_cmd.program);
+        cmd.args(create_cmd.args);
         self.cmd_spawn(cmd)
     }
     // list | ls
@@ -1152,6 +1742,60 @@ impl Distrobox {
         cmd.arg("stop").arg("--all").arg("--yes");
         self.cmd_output_string(cmd).await
     }
+    /// Reads the timezone of the host, like `Europe/Rome`
+    pub async fn host_timezone(&self) -> Result<String, Error> {
+        let cmd = Command::new_with_args(
+            "sh",
+            [
+                "-c",
+                "timedatectl show --property=Timezone --value 2>/dev/null || cat /etc/timezone 2>/dev/null || readlink /etc/localtime",
+            ],
+        );
+        let output = self.cmd_output_string(cmd).await?;
+        let line = output.lines().next().unwrap_or_default().trim();
+        // readlink gives a path like /usr/share/zoneinfo/Europe/Rome
+        let timezone = line.rsplit_once("zoneinfo/").map_or(line, |(_, tz)| tz);
+        validate_timezone(timezone)?;
+        Ok(timezone.to_string())
+    }
+    /// Sets the timezone of the container by pointing /etc/localtime to the zoneinfo file
+    pub async fn set_timezone(&self, name: &str, timezone: &str) -> Result<String, Error> {
+        validate_timezone(timezone)?;
+        let mut cmd = self.dbcmd();
+        cmd.args(["enter", "--name", name]).extend(
+            "--",
+            &Command::new_with_args(
+                "sudo",
+                [
+                    "sh",
+                    "-c",
+                    r#"ln -sf "/usr/share/zoneinfo/$1" /etc/localtime && echo "$1" > /etc/timezone"#,
+                    "sh",
+                    timezone,
+                ],
+            ),
+        );
+        self.cmd_output_string(cmd).await
+    }
+    /// Checks whether distrobox-init finished setting up the container, by looking for
+    /// the marker file it creates once the setup and its hooks succeed.
+    /// A container that isn't running, e.g. because its init failed, isn't set up.
+    pub async fn setup_completed(&self, name: &str) -> Result<bool, Error> {
+        let cmd = Command::new_with_args("sh", ["-c", SETUP_DONE_CHECK, "sh", name]);
+        let output = self.cmd_output_string(cmd).await?;
+        Ok(output.lines().any(|line| line.trim() == "done"))
+    }
+    /// Starts the container, returning once distrobox-init finished its setup
+    pub async fn start(&self, name: &str) -> Result<String, Error> {
+        let mut cmd = self.dbcmd();
+        cmd.args(["enter", "--name", name, "--", "true"]);
+        self.cmd_output_string(cmd).await
+    }
+    /// Restarts the container, so that distrobox-init runs the setup and its hooks again
+    pub async fn rerun_setup(&self, name: &str) -> Result<String, Error> {
+        self.stop(name).await?;
+        self.start(name).await
+    }
     // upgrade
     pub fn upgrade(&self, name: &str) -> Result<Box<dyn Child + Send>, Error> {
         let mut cmd = self.dbcmd();
@@ -1192,6 +1836,49 @@ impl Distrobox {
     // help
 }
 
+fn desktop_names_clash(desktop_file_name: &str, host_file_name: &str) -> bool {
+    let stem = |name: &str| {
+        name.trim()
+            .strip_suffix(".desktop")
+            .unwrap_or(name.trim())
+            .to_lowercase()
+    };
+    let app = stem(desktop_file_name);
+    let host = stem(host_file_name);
+    if app.is_empty() {
+        return false;
+    }
+    if app == host {
+        return true;
+    }
+    // "org.mozilla.firefox" -> "firefox"
+    let host_parts: Vec<&str> = host.split('.').collect();
+    host_parts.len() >= 3 && host_parts.last() == Some(&app.as_str())
+}
+
+/// Returns the exported file names of `box_name` without a matching source desktop file.
+fn find_stale_exports<'a>(
+    box_name: &str,
+    exported: &'a [String],
+    source_file_names: &[&str],
+    other_boxes: &[String],
+) -> Vec<&'a str> {
+    let prefix = format!("{box_name}-");
+    exported
+        .iter()
+        .map(|file| file.trim())
+        .filter(|file| file.ends_with(".desktop"))
+        .filter(|file| {
+            !other_boxes
+                .iter()
+                .any(|other| other.len() > box_name.len() && file.starts_with(&format!("{other}-")))
+        })
+        .filter_map(|file| file.strip_prefix(&prefix).map(|source| (file, source)))
+        .filter(|(_, source)| !source_file_names.contains(source))
+        .map(|(file, _)| file)
+        .collect()
+}
+
 impl Default for Distrobox {
     fn default() -> Self {
         Self::new(CommandRunner::new_null(), default_cmd_factory())
@@ -1386,11 +2073,327 @@ Categories=Utility;Security;";
         assert!(apps[0].exported);
         Ok(())
     }
+
+    #[test]
+    fn list_apps_with_busybox_grep() -> Result<(), Error> {
+        let desktop_files_toml = make_desktop_files_toml(
+            "/root",
+            &[(
+                "/usr/share/applications/htop.desktop",
+                "[Desktop Entry]\nName=Htop\nExec=htop\nIcon=htop",
+            )],
+            &[],
+        );
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(&["env", "-0"], "HOME=/home/me\0")
+                .cmd(&["ls", "/home/me/.local/share/applications"], "")
+                .cmd(
+                    &["distrobox", "enter", "alpine", "--", "sh", "-c", GREP_PROBE],
+                    "grep: unrecognized option: version\nBusyBox v1.36.1 (2024-06-10 07:11:47 UTC) multi-call binary.\n",
+                )
+                .cmd(
+                    &[
+                        "distrobox",
+                        "enter",
+                        "alpine",
+                        "--",
+                        "sh",
+                        "-c",
+                        BUSYBOX_FIND_AND_CONCAT_DESKTOP_FILES,
+                    ],
+                    &desktop_files_toml,
+                )
+                .build(),
+            default_cmd_factory(),
+        );
+
+        let output_tracker = db.cmd_runner.output_tracker();
+
+        output_tracker.enable();
+        assert_eq!(block_on(db.grep_flavor("alpine")), GrepFlavor::Busybox);
+        let apps = block_on(db.list_apps("alpine"))?;
+        assert_eq!(apps.len(), 1);
+        // The flavor is probed once per container
+        block_on(db.list_apps("alpine"))?;
+        let probes = output_tracker
+            .items()
+            .iter()
+            .filter(|event| {
+                event
+                    .command()
+                    .is_some_and(|cmd| cmd.to_string().ends_with(GREP_PROBE))
+            })
+            .count();
+        assert_eq!(probes, 1);
+        assert_eq!(&apps[0].entry.name, "Htop");
+        assert_eq!(
+            &apps[0].desktop_file_path,
+            "/usr/share/applications/htop.desktop"
+        );
+        assert!(!apps[0].exported);
+        Ok(())
+    }
+
+    #[test]
+    fn grep_flavor_from_probe() {
+        assert_eq!(
+            GrepFlavor::from_probe("grep (GNU grep) 3.11\nCopyright (C) 2023"),
+            GrepFlavor::Gnu
+        );
+        assert_eq!(
+            GrepFlavor::from_probe("grep: unrecognized option: version\nBusyBox v1.36.1"),
+            GrepFlavor::Busybox
+        );
+        assert_eq!(GrepFlavor::from_probe(""), GrepFlavor::Gnu);
+        assert_eq!(GrepFlavor::from_probe("no-grep\n"), GrepFlavor::Missing);
+    }
+
+    #[test]
+    fn list_apps_without_grep() {
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(&["env", "-0"], "HOME=/home/me\0")
+                .cmd(
+                    &[
+                        "distrobox",
+                        "enter",
+                        "scratch",
+                        "--",
+                        "sh",
+                        "-c",
+                        GREP_PROBE,
+                    ],
+                    "no-grep\n",
+                )
+                .build(),
+            default_cmd_factory(),
+        );
+
+        assert!(matches!(
+            block_on(db.list_apps("scratch")),
+            Err(Error::MissingGrep)
+        ));
+    }
+
+    #[test]
+    fn list_stale_exports() -> Result<(), Error> {
+        let vim_desktop = "[Desktop Entry]
+Type=Application
+Name=Vim
+Exec=/path/to/vim";
+        let desktop_files_toml = make_desktop_files_toml(
+            "/home/me",
+            &[("/usr/share/applications/vim.desktop", vim_desktop)],
+            &[],
+        );
+        let ls_output = "ID           | NAME                 | STATUS             | IMAGE
+d24405b14180 | ubuntu               | Created            | docker.io/library/ubuntu:latest
+a0c4e3b2f1d9 | ubuntu-dev           | Created            | docker.io/library/ubuntu:latest";
+
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(&["env", "-0"], "HOME=/home/me\0")
+                .cmd(
+                    &["ls", "/home/me/.local/share/applications"],
+                    "firefox.desktop\nubuntu-vim.desktop\nubuntu-gimp.desktop\nubuntu-dev-code.desktop\n",
+                )
+                .cmd(
+                    &[
+                        "distrobox",
+                        "enter",
+                        "ubuntu",
+                        "--",
+                        "sh",
+                        "-c",
+                        POSIX_FIND_AND_CONCAT_DESKTOP_FILES,
+                    ],
+                    &desktop_files_toml,
+                )
+                .cmd(&["distrobox", "ls", "--no-color"], ls_output)
+                .build(),
+            default_cmd_factory(),
+        );
+
+        let stale = block_on(db.list_stale_exports("ubuntu"))?;
+        assert_eq!(
+            stale,
+            vec![StaleExport {
+                file_name: "ubuntu-gimp.desktop".into(),
+                host_path: PathBuf::from("/home/me/.local/share/applications/ubuntu-gimp.desktop"),
+            }]
+        );
+
+        let output_tracker = db.cmd_runner.output_tracker();
+
+        output_tracker.enable();
+        block_on(db.remove_stale_export(&stale[0]))?;
+        assert_eq!(
+            output_tracker.items()[0].command().unwrap().to_string(),
+            "rm -f -- /home/me/.local/share/applications/ubuntu-gimp.desktop"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn list_all_exports() -> Result<(), Error> {
+        let ls_output = "ID           | NAME                 | STATUS             | IMAGE
+d24405b14180 | ubuntu               | Created            | docker.io/library/ubuntu:latest
+a0c4e3b2f1d9 | fedora               | Up 2 minutes       | registry.fedoraproject.org/fedora:40";
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(&["env", "-0"], "HOME=/home/me\0")
+                .cmd(
+                    &["ls", "/home/me/.local/share/applications"],
+                    "firefox.desktop\nubuntu-vim.desktop\nfedora-code.desktop\narch-htop.desktop\nubuntu-notes.txt\nfedora-gimp.desktop\n",
+                )
+                .cmd(&["distrobox", "ls", "--no-color"], ls_output)
+                .build(),
+            default_cmd_factory(),
+        );
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+
+        assert_eq!(
+            block_on(db.list_all_exports())?,
+            [
+                "ubuntu-vim.desktop",
+                "fedora-code.desktop",
+                "fedora-gimp.desktop"
+            ]
+        );
+
+        block_on(db.backup_exports(Path::new("/backup/apps.tar.gz")))?;
+        assert_eq!(
+            output_tracker
+                .items()
+                .iter()
+                .rev()
+                .find_map(|event| event.command())
+                .unwrap()
+                .to_string(),
+            "tar -czf /backup/apps.tar.gz -C /home/me/.local/share/applications -- ubuntu-vim.desktop fedora-code.desktop fedora-gimp.desktop"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn host_has_desktop() -> Result<(), Error> {
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(
+                    &["env", "-0"],
+                    "HOME=/home/me\0XDG_DATA_DIRS=/usr/share:/var/lib/flatpak/exports/share\0",
+                )
+                .cmd(
+                    &[
+                        "sh",
+                        "-c",
+                        r#"for d in "$@"; do ls -1 "$d" 2>/dev/null; done; true"#,
+                        "sh",
+                        "/home/me/.local/share/applications",
+                        "/usr/share/applications",
+                        "/var/lib/flatpak/exports/share/applications",
+                    ],
+                    "ubuntu-vim.desktop\nmimeinfo.cache\norg.gnome.Nautilus.desktop\nOrg.Mozilla.Firefox.desktop\nhtop.desktop\n",
+                )
+                .build(),
+            default_cmd_factory(),
+        );
+
+        assert!(block_on(db.host_has_desktop("firefox.desktop"))?);
+        assert!(block_on(db.host_has_desktop("htop.desktop"))?);
+        assert!(block_on(db.host_has_desktop("org.gnome.Nautilus.desktop"))?);
+        // Exports of other containers aren't host apps
+        assert!(!block_on(db.host_has_desktop("vim.desktop"))?);
+        assert!(!block_on(db.host_has_desktop("gimp.desktop"))?);
+        // Only reverse-DNS names are matched by their last component
+        assert!(!block_on(db.host_has_desktop("gnome.desktop"))?);
+        Ok(())
+    }
+
+    #[test]
+    fn rename_exported_app() -> Result<(), Error> {
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(&["env", "-0"], "HOME=/home/me\0")
+                .build(),
+            default_cmd_factory(),
+        );
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+        block_on(db.rename_exported_app("ubuntu", "firefox.desktop", "Firefox A/B"))?;
+        let commands: Vec<String> = output_tracker
+            .items()
+            .iter()
+            .filter_map(|event| event.command().map(|cmd| cmd.to_string()))
+            .collect();
+        assert_eq!(
+            commands.last().unwrap(),
+            r"sed -i /^\[Desktop Entry\]/,/^\[/ { /^Name\[/d; s/^Name=.*/Name=Firefox A\/B/; } /home/me/.local/share/applications/ubuntu-firefox.desktop"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn export_dedicated_session() -> Result<(), Error> {
+        let check = |name: &str| {
+            Command::new_with_args(
+                "distrobox",
+                [
+                    "enter",
+                    "--name",
+                    name,
+                    "--",
+                    "sh",
+                    "-c",
+                    DBUS_RUN_SESSION_CHECK,
+                ],
+            )
+        };
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(&["env", "-0"], "HOME=/home/me\0")
+                .cmd_full(check("ubuntu"), || Ok("found\n".into()))
+                .cmd_full(check("alpine"), || Ok("missing\n".into()))
+                .build(),
+            default_cmd_factory(),
+        );
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+        let commands = || -> Vec<String> {
+            output_tracker
+                .items()
+                .iter()
+                .filter_map(|event| event.command().map(|cmd| cmd.to_string()))
+                .collect()
+        };
+
+        assert!(block_on(
+            db.export_dedicated_session("ubuntu", "firefox.desktop")
+        )?);
+        assert_eq!(
+            commands().last().unwrap(),
+            r"sed -i /^Exec=/ { /dbus-run-session/! s/\(-n \+ubuntu \+-- \+\)/\1dbus-run-session -- /; } /home/me/.local/share/applications/ubuntu-firefox.desktop"
+        );
+
+        // Without dbus-run-session, the exported app keeps sharing the container session
+        assert!(!block_on(
+            db.export_dedicated_session("alpine", "firefox.desktop")
+        )?);
+        assert_eq!(
+            commands().last().unwrap(),
+            &format!("distrobox enter --name alpine -- sh -c {DBUS_RUN_SESSION_CHECK}")
+        );
+        Ok(())
+    }
+
     #[test]
     fn create() -> Result<(), Error> {
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
         let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
         let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
         debug!("Testing container creation");
         let args = CreateArgs {
             image: Some(CreateArgsImage::new("docker.io/library/ubuntu:latest").unwrap()),
@@ -1414,10 +2417,104 @@ Categories=Utility;Security;";
         Ok(())
     }
 
+    #[test]
+    fn clone_into_another_manager() -> Result<(), Error> {
+        let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+
+        let args = CreateArgs {
+            name: CreateArgName::new("ubuntu-docker").unwrap(),
+            container_manager: Some(ContainerManager::Docker),
+            ..Default::default()
+        };
+        block_on(db.clone_from("ubuntu", Some(ContainerManager::Podman), args.clone()))?;
+        let command = output_tracker.items()[0].command().unwrap().clone();
+        assert_eq!(command.program, "sh");
+        let script_args: Vec<_> = command.args[2..]
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            script_args.join(" "),
+            "sh podman ubuntu localhost/ubuntu-docker:clone docker env DBX_CONTAINER_MANAGER=docker distrobox create --yes --image localhost/ubuntu-docker:clone --name ubuntu-docker"
+        );
+
+        // Within the same manager, distrobox clones by itself
+        block_on(db.clone_from("ubuntu", Some(ContainerManager::Docker), args))?;
+        assert_eq!(
+            output_tracker.items()[1].command().unwrap().to_string(),
+            "env DBX_CONTAINER_MANAGER=docker distrobox create --yes --name ubuntu-docker --clone ubuntu"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_timezone() -> Result<(), Error> {
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(
+                    &[
+                        "sh",
+                        "-c",
+                        "timedatectl show --property=Timezone --value 2>/dev/null || cat /etc/timezone 2>/dev/null || readlink /etc/localtime",
+                    ],
+                    "/usr/share/zoneinfo/America/Argentina/Buenos_Aires\n",
+                )
+                .build(),
+            default_cmd_factory(),
+        );
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+
+        let timezone = block_on(db.host_timezone())?;
+        assert_eq!(timezone, "America/Argentina/Buenos_Aires");
+
+        block_on(db.set_timezone("ubuntu", &timezone))?;
+        let events = output_tracker.items();
+        assert_eq!(
+            events
+                .iter()
+                .rev()
+                .find_map(|event| event.command())
+                .unwrap()
+                .to_string(),
+            r#"distrobox enter --name ubuntu -- sudo sh -c ln -sf "/usr/share/zoneinfo/$1" /etc/localtime && echo "$1" > /etc/timezone sh America/Argentina/Buenos_Aires"#
+        );
+
+        assert!(block_on(db.set_timezone("ubuntu", "../../etc/shadow")).is_err());
+        assert!(block_on(db.set_timezone("ubuntu", "Europe/Rome; rm -rf /")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn setup_completed() -> Result<(), Error> {
+        let db = Distrobox::new(
+            NullCommandRunnerBuilder::new()
+                .cmd(&["sh", "-c", SETUP_DONE_CHECK, "sh", "ubuntu"], "done\n")
+                .cmd(&["sh", "-c", SETUP_DONE_CHECK, "sh", "fedora"], "missing\n")
+                .build(),
+            default_cmd_factory(),
+        );
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+
+        assert!(block_on(db.setup_completed("ubuntu"))?);
+        assert!(!block_on(db.setup_completed("fedora"))?);
+        let command = output_tracker.items()[0].command().unwrap().clone();
+        assert_eq!(command.program, "sh");
+        assert_eq!(
+            command.args,
+            ["-c", SETUP_DONE_CHECK, "sh", "ubuntu"].map(std::ffi::OsString::from)
+        );
+        Ok(())
+    }
+
     #[test]
     fn create_with_no_entry() -> Result<(), Error> {
         let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
         let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
         let args = CreateArgs {
             image: Some(CreateArgsImage::new("docker.io/library/ubuntu:latest").unwrap()),
             no_entry: true,
@@ -1435,6 +2532,7 @@ Categories=Utility;Security;";
     fn assemble() -> Result<(), Error> {
         let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
         let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
         db.assemble("/path/to/assemble.yml")?;
         assert_eq!(
             output_tracker.items()[0].command().unwrap().to_string(),
@@ -1443,10 +2541,171 @@ Categories=Utility;Security;";
         Ok(())
     }
 
+    #[test]
+    fn launch_app() -> Result<(), Error> {
+        let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+        let app = ExportableApp {
+            entry: DesktopEntry {
+                name: "Firefox".into(),
+                exec: "firefox %u".into(),
+                icon: "firefox".into(),
+                ..Default::default()
+            },
+            desktop_file_path: "/usr/share/applications/firefox.desktop".into(),
+            exported: false,
+        };
+        db.launch_app("ubuntu", &app, &LaunchSession::Shared)?;
+        db.launch_app("ubuntu", &app, &LaunchSession::Dedicated)?;
+        let commands: Vec<String> = output_tracker
+            .items()
+            .iter()
+            .map(|event| event.command().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                "distrobox enter --name ubuntu -- firefox",
+                format!(
+                    "distrobox enter --name ubuntu -- sh -c {DEDICATED_SESSION_SCRIPT} sh firefox"
+                )
+                .as_str(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn group_apps_by_primary_category() {
+        let app = |name: &str, categories: &[&str], exported: bool| ExportableApp {
+            entry: DesktopEntry {
+                name: name.into(),
+                exec: name.into(),
+                categories: categories.iter().map(|c| c.to_string()).collect(),
+                ..Default::default()
+            },
+            desktop_file_path: format!("/usr/share/applications/{name}.desktop"),
+            exported,
+        };
+        let groups = group_apps_by_category([
+            app("code", &["IDE", "Development"], false),
+            app("gimp", &["Graphics", "2DGraphics"], false),
+            app("htop", &["ConsoleOnly"], false),
+            app("builder", &["Development", "GNOME"], true),
+            app("meld", &["GNOME", "Development"], false),
+        ]);
+        let summary: Vec<(Option<&str>, Vec<&str>)> = groups
+            .iter()
+            .map(|group| {
+                let names = group.apps.iter().map(|app| app.entry.name.as_str());
+                (group.category.as_deref(), names.collect())
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some("Development"), vec!["code", "builder", "meld"]),
+                (Some("Graphics"), vec!["gimp"]),
+                (None, vec!["htop"]),
+            ]
+        );
+        assert_eq!(
+            groups[0].unexported_paths(),
+            [
+                "/usr/share/applications/code.desktop",
+                "/usr/share/applications/meld.desktop",
+            ]
+        );
+    }
+
+    #[test]
+    fn launch_app_with_env() -> Result<(), Error> {
+        let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
+        let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
+        let app = ExportableApp {
+            entry: DesktopEntry {
+                name: "Text Editor".into(),
+                exec: "gnome-text-editor %U".into(),
+                icon: "org.gnome.TextEditor".into(),
+                ..Default::default()
+            },
+            desktop_file_path: "/usr/share/applications/org.gnome.TextEditor.desktop".into(),
+            exported: false,
+        };
+        let env = parse_env_assignments("GTK_DEBUG=interactive G_MESSAGES_DEBUG=all")?;
+        db.launch_app_with_env("ubuntu", &app, &LaunchSession::Shared, &env)?;
+        db.launch_app_with_env(
+            "ubuntu",
+            &app,
+            &LaunchSession::Shared,
+            &[("LANG".into(), "C UTF-8".into())],
+        )?;
+        // The env prefix is part of the single command argument, as the Exec line is
+        let args = &output_tracker.items()[0].command().unwrap().args;
+        assert_eq!(
+            args.last().unwrap(),
+            "env GTK_DEBUG=interactive G_MESSAGES_DEBUG=all gnome-text-editor"
+        );
+        assert_eq!(
+            output_tracker.items()[1].command().unwrap().to_string(),
+            "distrobox enter --name ubuntu -- env LANG='C UTF-8' gnome-text-editor"
+        );
+
+        let bad_name = [("1ABC".to_string(), "x".to_string())];
+        assert!(
+            db.launch_app_with_env("ubuntu", &app, &LaunchSession::Shared, &bad_name)
+                .is_err()
+        );
+        assert!(parse_env_assignments("GTK_DEBUG").is_err());
+        assert!(parse_env_assignments("").unwrap().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn create_with_copied_volumes() {
+        let bind = |source: &str, destination: &str, read_only: bool| Mount {
+            kind: "bind".into(),
+            source: source.into(),
+            destination: destination.into(),
+            read_only,
+        };
+        let source_mounts = [
+            bind("/home/me", "/home/me", false),
+            bind("/", "/run/host", false),
+            bind("/tmp", "/tmp", false),
+            bind("/run/user/1000", "/run/user/1000", false),
+            bind("/usr/bin/distrobox-init", "/usr/bin/entrypoint", true),
+            bind("/srv/data", "/data", false),
+            bind("/opt/tools", "/tools", true),
+            Mount {
+                kind: "volume".into(),
+                source: "/var/lib/containers/storage/volumes/cache/_data".into(),
+                destination: "/cache".into(),
+                read_only: false,
+            },
+        ];
+
+        let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
+        let args = CreateArgs {
+            name: CreateArgName::new("sibling").unwrap(),
+            image: Some(CreateArgsImage::new("docker.io/library/ubuntu:latest").unwrap()),
+            volumes: user_volumes(&source_mounts, "/home/me"),
+            ..Default::default()
+        };
+        assert_eq!(
+            db.create_cmd(args).to_string(),
+            "distrobox create --yes --image docker.io/library/ubuntu:latest --name sibling \
+             --volume /srv/data:/data --volume /opt/tools:/tools:ro"
+        );
+    }
+
     #[test]
     fn remove() -> Result<(), Error> {
         let db = Distrobox::new(CommandRunner::new_null(), default_cmd_factory());
         let output_tracker = db.cmd_runner.output_tracker();
+        output_tracker.enable();
         block_on(db.remove("ubuntu"))?;
         assert_eq!(
             output_tracker.items()[0].command().unwrap().to_string(),
@@ -1614,6 +2873,7 @@ Categories=Utility;Security;";
             host_path: "/host".to_string(),
             container_path: "/container".to_string(),
             mode: None,
+            relabel: None,
         };
         assert_eq!(vol.to_string(), "/host:/container");
 
@@ -1621,10 +2881,30 @@ Categories=Utility;Security;";
             host_path: "/host".to_string(),
             container_path: "/container".to_string(),
             mode: Some(VolumeMode::ReadOnly),
+            relabel: None,
         };
         assert_eq!(vol_ro.to_string(), "/host:/container:ro");
     }
 
+    #[test]
+    fn volume_toggle_modes() -> Result<(), Error> {
+        let vol = Volume::from_str("/data:/data:z")?;
+        assert_eq!(vol.relabel, Some(VolumeRelabel::Shared));
+
+        let vol = vol.with_read_only(true);
+        assert_eq!(vol.to_string(), "/data:/data:ro,z");
+        assert_eq!(Volume::from_str(&vol.to_string())?, vol);
+
+        let vol = vol.with_relabel(Some(VolumeRelabel::Private));
+        assert_eq!(vol.to_string(), "/data:/data:ro,Z");
+
+        let vol = vol.with_read_only(false).with_relabel(None);
+        assert_eq!(vol.to_string(), "/data:/data");
+
+        assert!(Volume::from_str("/data:/data:ro,x").is_err());
+        Ok(())
+    }
+
     #[test]
     fn container_info_parsing() -> Result<(), Error> {
         // Test valid container line with "Up" status
@@ -1,3 +1,4 @@
+pub mod assemble;
 pub mod command;
 #[allow(clippy::module_inception)]
 mod distrobox;
@@ -3,7 +3,10 @@ use std::{collections::HashSet, rc::Rc};
 use async_trait::async_trait;
 
 use crate::{
-    backends::container_runtime::{ContainerRuntime, Usage},
+    backends::container_runtime::{
+        ContainerRuntime, Mount, NetworkInfo, RuntimeContainer, Usage, parse_container_image_id,
+        parse_container_list, parse_mounts, parse_network_info, parse_repo_digest,
+    },
     fakers::{Command, CommandRunner},
     root_store::Image,
 };
@@ -65,6 +68,72 @@ impl ContainerRuntime for Docker {
         Ok(names)
     }
 
+    async fn list_all_containers(&self) -> anyhow::Result<Vec<RuntimeContainer>> {
+        let mut cmd = Command::new("docker");
+        cmd.arg("ps").arg("--all").arg("--format").arg("json");
+
+        let output = self.cmd_runner.output_string(cmd).await?;
+        parse_container_list(&output)
+    }
+
+    async fn network_info(&self, name: &str) -> anyhow::Result<NetworkInfo> {
+        let mut cmd = Command::new("docker");
+        cmd.arg("inspect").arg("--type").arg("container").arg(name);
+
+        let output = self.cmd_runner.output_string(cmd).await?;
+        parse_network_info(&output)
+    }
+
+    async fn mounts(&self, name: &str) -> anyhow::Result<Vec<Mount>> {
+        let mut cmd = Command::new("docker");
+        cmd.arg("inspect").arg("--type").arg("container").arg(name);
+
+        let output = self.cmd_runner.output_string(cmd).await?;
+        parse_mounts(&output)
+    }
+
+    async fn image_exists(&self, image: &str) -> anyhow::Result<bool> {
+        let mut cmd = Command::new("docker");
+        cmd.arg("image").arg("inspect").arg(image);
+        cmd.stdout = crate::fakers::FdMode::Pipe;
+        cmd.stderr = crate::fakers::FdMode::Pipe;
+
+        let output = self.cmd_runner.output(cmd).await?;
+        Ok(output.status.success())
+    }
+
+    async fn container_image_id(&self, name: &str) -> anyhow::Result<String> {
+        let mut cmd = Command::new("docker");
+        cmd.arg("inspect").arg("--type").arg("container").arg(name);
+
+        let output = self.cmd_runner.output_string(cmd).await?;
+        parse_container_image_id(&output)
+    }
+
+    async fn image_digest(&self, image: &str) -> anyhow::Result<String> {
+        let mut cmd = Command::new("docker");
+        cmd.arg("image").arg("inspect").arg(image);
+
+        let output = self.cmd_runner.output_string(cmd).await?;
+        parse_repo_digest(&output)
+    }
+
+    async fn stop_container(&self, name: &str) -> anyhow::Result<()> {
+        let mut cmd = Command::new("docker");
+        cmd.arg("stop").arg(name);
+        cmd.stdout = crate::fakers::FdMode::Pipe;
+        cmd.stderr = crate::fakers::FdMode::Pipe;
+
+        let output = self.cmd_runner.output(cmd).await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to stop {name}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
     async fn usage(&self, container_id: &str) -> anyhow::Result<Usage> {
         let mut cmd = Command::new("docker");
         cmd.arg("stats");
@@ -3,7 +3,9 @@ pub mod desktop_file;
 pub mod distrobox;
 pub mod docker;
 pub mod flatpak;
+pub mod package_changes;
 pub mod podman;
 pub mod supported_terminals;
 
 pub use distrobox::*;
+pub use package_changes::PackageChanges;
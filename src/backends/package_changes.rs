@@ -0,0 +1,244 @@
+use std::fmt;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap());
+// e.g. "(2/5) upgrading curl                [#####] 100%"
+static PACMAN_ACTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\(\s*\d+/\d+\) (upgrading|installing|removing) (\S+)").unwrap());
+
+/// The packages changed by an upgrade, as reported by the package manager of the container
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackageChanges {
+    pub upgraded: Vec<String>,
+    pub installed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl PackageChanges {
+    /// Parses the output of `distrobox upgrade`, which runs apt, dnf or pacman
+    pub fn parse(output: &str) -> Self {
+        let output = ANSI_ESCAPE.replace_all(output, "");
+        let lines: Vec<&str> = output
+            .split(['\n', '\r'])
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+
+        let mut changes = Self::default();
+        changes.parse_apt(&lines);
+        changes.parse_dnf(&lines);
+        changes.parse_pacman(&lines);
+        for list in [
+            &mut changes.upgraded,
+            &mut changes.installed,
+            &mut changes.removed,
+        ] {
+            list.sort();
+            list.dedup();
+        }
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.upgraded.is_empty() && self.installed.is_empty() && self.removed.is_empty()
+    }
+
+    fn list_for_section(&mut self, header: &str) -> Option<&mut Vec<String>> {
+        let header = header.to_lowercase();
+        if header.starts_with("upgrading") || header.contains("will be upgraded") {
+            Some(&mut self.upgraded)
+        } else if header.starts_with("installing") || header.contains("will be installed") {
+            Some(&mut self.installed)
+        } else if header.starts_with("removing") || header.contains("will be removed") {
+            Some(&mut self.removed)
+        } else {
+            None
+        }
+    }
+
+    /// apt lists the packages of each kind of change in an indented block:
+    /// "The following packages will be upgraded:\n  curl libcurl4"
+    fn parse_apt(&mut self, lines: &[&str]) {
+        let mut section: Option<String> = None;
+        for line in lines {
+            if !line.starts_with(' ') {
+                section = (line.starts_with("The following") && line.ends_with(':'))
+                    .then(|| line.to_string());
+                continue;
+            }
+            let Some(list) = section.as_deref().and_then(|s| self.list_for_section(s)) else {
+                continue;
+            };
+            // Removed packages get a '*' suffix when their configuration is purged too
+            list.extend(
+                line.split_whitespace()
+                    .map(|name| name.trim_end_matches('*').to_string()),
+            );
+        }
+    }
+
+    /// dnf prints a table with a section per kind of change, the package name in the first
+    /// column: "Upgrading:\n curl   x86_64   8.2.1-3.fc39   updates   343 k"
+    fn parse_dnf(&mut self, lines: &[&str]) {
+        let mut section: Option<String> = None;
+        for line in lines {
+            if !line.starts_with(' ') {
+                section = line
+                    .ends_with(':')
+                    .then(|| line.to_string())
+                    .filter(|s| !s.starts_with("The following"));
+                continue;
+            }
+            // dnf5 lists the replaced version below each upgraded package
+            if line.trim_start().starts_with("replacing ") {
+                continue;
+            }
+            let Some(list) = section.as_deref().and_then(|s| self.list_for_section(s)) else {
+                continue;
+            };
+            // Skips wrapped lines, which hold the remaining columns of a long package name
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() >= 4 {
+                list.push(columns[0].to_string());
+            }
+        }
+    }
+
+    fn parse_pacman(&mut self, lines: &[&str]) {
+        for line in lines {
+            let Some(captures) = PACMAN_ACTION.captures(line.trim_start()) else {
+                continue;
+            };
+            let list = match &captures[1] {
+                "upgrading" => &mut self.upgraded,
+                "installing" => &mut self.installed,
+                _ => &mut self.removed,
+            };
+            list.push(captures[2].trim_end_matches("...").to_string());
+        }
+    }
+}
+
+impl fmt::Display for PackageChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No packages changed");
+        }
+        let sections = [
+            ("Upgraded", &self.upgraded),
+            ("Installed", &self.installed),
+            ("Removed", &self.removed),
+        ];
+        let lines: Vec<String> = sections
+            .iter()
+            .filter(|(_, packages)| !packages.is_empty())
+            .map(|(label, packages)| {
+                format!("{label} ({}): {}", packages.len(), packages.join(", "))
+            })
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dnf_upgrade() {
+        let output = "\
+Last metadata expiration check: 0:12:01 ago on Tue 14 Oct 2025 10:02:11 AM UTC.
+Dependencies resolved.
+================================================================================
+ Package              Arch      Version                 Repository        Size
+================================================================================
+Upgrading:
+ curl                 x86_64    8.2.1-3.fc39            updates          343 k
+ libcurl              x86_64    8.2.1-3.fc39            updates          321 k
+ vim-minimal          x86_64    2:9.1.031-1.fc39        updates          808 k
+Installing:
+ kernel-core          x86_64    6.7.4-100.fc39          updates           16 M
+Removing:
+ kernel-core          x86_64    6.5.6-300.fc39          @anaconda         62 M
+
+Transaction Summary
+================================================================================
+Install  1 Package
+Upgrade  3 Packages
+Remove   1 Package
+
+Total download size: 17 M
+Complete!
+";
+        let changes = PackageChanges::parse(output);
+        assert_eq!(changes.upgraded, ["curl", "libcurl", "vim-minimal"]);
+        assert_eq!(changes.installed, ["kernel-core"]);
+        assert_eq!(changes.removed, ["kernel-core"]);
+    }
+
+    #[test]
+    fn parse_dnf5_upgrade() {
+        let output = "\
+Package                  Arch   Version                  Repository      Size
+Upgrading:
+ curl                    x86_64 8.6.0-7.fc40             updates    754.0 KiB
+   replacing curl        x86_64 8.6.0-6.fc40             fedora     754.0 KiB
+
+Transaction Summary:
+ Upgrading:          1 package
+";
+        let changes = PackageChanges::parse(output);
+        assert_eq!(changes.upgraded, ["curl"]);
+        assert!(changes.installed.is_empty());
+    }
+
+    #[test]
+    fn parse_apt_upgrade() {
+        let output = "\
+Reading package lists...
+The following NEW packages will be installed:
+  linux-image-6.8.0-45
+The following packages will be upgraded:
+  \x1b[1mlibc-bin libc6\x1b[0m
+  tzdata
+The following packages will be REMOVED:
+  linux-image-6.8.0-40*
+3 upgraded, 1 newly installed, 1 to remove and 0 not upgraded.
+";
+        let changes = PackageChanges::parse(output);
+        assert_eq!(changes.upgraded, ["libc-bin", "libc6", "tzdata"]);
+        assert_eq!(changes.installed, ["linux-image-6.8.0-45"]);
+        assert_eq!(changes.removed, ["linux-image-6.8.0-40"]);
+    }
+
+    #[test]
+    fn parse_pacman_upgrade() {
+        let output = "\
+:: Starting full system upgrade...
+(1/3) checking keys in keyring                     [######################] 100%
+(1/3) upgrading curl                               [######################] 100%
+(2/3) installing libnghttp3                        [######################] 100%
+(3/3) removing libgcrypt15...\r(3/3) removing libgcrypt15 [######] 100%
+";
+        let changes = PackageChanges::parse(output);
+        assert_eq!(changes.upgraded, ["curl"]);
+        assert_eq!(changes.installed, ["libnghttp3"]);
+        assert_eq!(changes.removed, ["libgcrypt15"]);
+    }
+
+    #[test]
+    fn summary() {
+        let changes = PackageChanges {
+            upgraded: vec!["curl".into(), "libcurl".into()],
+            installed: vec![],
+            removed: vec!["vim".into()],
+        };
+        assert_eq!(
+            changes.to_string(),
+            "Upgraded (2): curl, libcurl\nRemoved (1): vim"
+        );
+        assert_eq!(PackageChanges::default().to_string(), "No packages changed");
+    }
+}
@@ -10,7 +10,7 @@ use futures::Stream;
 use serde::Deserialize;
 
 use crate::{
-    backends::container_runtime::{ContainerRuntime, Usage},
+    backends::container_runtime::{ContainerRuntime, Mount, NetworkInfo, RuntimeContainer, Usage},
     fakers::{Child, Command, CommandRunner, FdMode},
 };
 
@@ -135,6 +135,34 @@ impl ContainerRuntime for Podman {
     async fn downloaded_images(&self) -> anyhow::Result<HashSet<String>> {
         self.docker.downloaded_images().await
     }
+
+    async fn list_all_containers(&self) -> anyhow::Result<Vec<RuntimeContainer>> {
+        self.docker.list_all_containers().await
+    }
+
+    async fn network_info(&self, name: &str) -> anyhow::Result<NetworkInfo> {
+        self.docker.network_info(name).await
+    }
+
+    async fn mounts(&self, name: &str) -> anyhow::Result<Vec<Mount>> {
+        self.docker.mounts(name).await
+    }
+
+    async fn image_exists(&self, image: &str) -> anyhow::Result<bool> {
+        self.docker.image_exists(image).await
+    }
+
+    async fn container_image_id(&self, name: &str) -> anyhow::Result<String> {
+        self.docker.container_image_id(name).await
+    }
+
+    async fn image_digest(&self, image: &str) -> anyhow::Result<String> {
+        self.docker.image_digest(image).await
+    }
+
+    async fn stop_container(&self, name: &str) -> anyhow::Result<()> {
+        self.docker.stop_container(name).await
+    }
 }
 
 #[cfg(test)]
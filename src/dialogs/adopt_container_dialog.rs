@@ -0,0 +1,198 @@
+use crate::backends::container_runtime::RuntimeContainer;
+use crate::gettext_f;
+use crate::i18n::gettext;
+use crate::models::RootStore;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{Properties, clone, derived_properties};
+use gtk::glib;
+use std::cell::RefCell;
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::AdoptContainerDialog)]
+    pub struct AdoptContainerDialog {
+        #[property(get, set, construct)]
+        pub root_store: RefCell<RootStore>,
+        pub toast_overlay: adw::ToastOverlay,
+        pub stack: gtk::Stack,
+        pub containers_group: adw::PreferencesGroup,
+        pub list_box: gtk::ListBox,
+        pub error_page: adw::StatusPage,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AdoptContainerDialog {
+        const NAME: &'static str = "AdoptContainerDialog";
+        type Type = super::AdoptContainerDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    #[derived_properties]
+    impl ObjectImpl for AdoptContainerDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title(&gettext("Adopt Container"));
+            obj.set_content_width(480);
+            obj.set_content_height(560);
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+            let loading_page = adw::StatusPage::new();
+            loading_page.set_title(&gettext("Loading Containers"));
+            loading_page.set_child(Some(&adw::Spinner::new()));
+            self.stack.add_named(&loading_page, Some("loading"));
+
+            self.error_page.set_icon_name(Some("dialog-error-symbolic"));
+            self.error_page
+                .set_title(&gettext("Could not list the containers"));
+            self.stack.add_named(&self.error_page, Some("error"));
+
+            let empty_page = adw::StatusPage::new();
+            empty_page.set_title(&gettext("No Other Containers"));
+            empty_page.set_description(Some(&gettext(
+                "Every container of the runtime is already managed by distrobox.",
+            )));
+            self.stack.add_named(&empty_page, Some("empty"));
+
+            self.list_box.add_css_class("boxed-list");
+            self.list_box.set_selection_mode(gtk::SelectionMode::None);
+            self.containers_group
+                .set_title(&gettext("Other Containers"));
+            self.containers_group.set_description(Some(&gettext(
+                "These containers weren't created by distrobox. Adopting one clones it into a new distrobox, leaving the original in place. A running container is stopped first.",
+            )));
+            self.containers_group.add(&self.list_box);
+
+            let page = adw::PreferencesPage::new();
+            page.add(&self.containers_group);
+            self.stack.add_named(&page, Some("containers"));
+            self.stack.set_visible_child_name("loading");
+
+            self.toast_overlay.set_child(Some(&self.stack));
+            toolbar_view.set_content(Some(&self.toast_overlay));
+            obj.set_child(Some(&toolbar_view));
+
+            obj.load_containers();
+        }
+    }
+
+    impl WidgetImpl for AdoptContainerDialog {}
+    impl AdwDialogImpl for AdoptContainerDialog {}
+}
+
+glib::wrapper! {
+    pub struct AdoptContainerDialog(ObjectSubclass<imp::AdoptContainerDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl AdoptContainerDialog {
+    pub fn new(root_store: RootStore) -> Self {
+        glib::Object::builder()
+            .property("root-store", root_store)
+            .build()
+    }
+
+    fn load_containers(&self) {
+        let this = self.clone();
+        glib::spawn_future_local(async move {
+            let imp = this.imp();
+            match this.root_store().list_plain_containers().await {
+                Ok(containers) if containers.is_empty() => {
+                    imp.stack.set_visible_child_name("empty");
+                }
+                Ok(containers) => {
+                    imp.list_box.remove_all();
+                    for container in &containers {
+                        imp.list_box.append(&this.build_row(container));
+                    }
+                    imp.stack.set_visible_child_name("containers");
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to list the runtime containers");
+                    imp.error_page.set_description(Some(&e.to_string()));
+                    imp.stack.set_visible_child_name("error");
+                }
+            }
+        });
+    }
+
+    fn build_row(&self, container: &RuntimeContainer) -> adw::ActionRow {
+        let row = adw::ActionRow::new();
+        row.set_title(&container.name);
+        row.set_subtitle(&format!("{} · {}", container.image, container.state));
+
+        let adopt_btn = gtk::Button::with_label(&gettext("Adopt"));
+        adopt_btn.set_valign(gtk::Align::Center);
+        let container = container.clone();
+        adopt_btn.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                if container.is_running() {
+                    this.confirm_stop(&container);
+                } else {
+                    this.adopt(&container, false);
+                }
+            }
+        ));
+        row.add_suffix(&adopt_btn);
+
+        row
+    }
+
+    // Distrobox can only clone a stopped container
+    fn confirm_stop(&self, container: &RuntimeContainer) {
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Stop the container?"))
+            .body(gettext_f!(
+                "{name} is running. It has to be stopped before it can be adopted.",
+                "name" => container.name
+            ))
+            .close_response("cancel")
+            .default_response("cancel")
+            .build();
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("stop-adopt", &gettext("Stop and Adopt"));
+        dialog.set_response_appearance("stop-adopt", adw::ResponseAppearance::Destructive);
+
+        let container = container.clone();
+        dialog.connect_response(
+            None,
+            clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_dialog, response| {
+                    if response == "stop-adopt" {
+                        this.adopt(&container, true);
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    fn adopt(&self, container: &RuntimeContainer, stop_first: bool) {
+        let this = self.clone();
+        let container = container.clone();
+        glib::spawn_future_local(async move {
+            // On success the task manager replaces this dialog
+            if let Err(e) = this
+                .root_store()
+                .adopt_container(&container, stop_first)
+                .await
+            {
+                this.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new(&e.to_string()));
+            }
+        });
+    }
+}
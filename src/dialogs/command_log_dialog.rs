@@ -1,11 +1,14 @@
-use crate::fakers::CommandRunnerEvent;
+use crate::fakers::{Command, CommandRunnerEvent};
+use crate::gettext_f;
 use crate::i18n::gettext;
 use crate::models::RootStore;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::{Properties, derived_properties};
-use gtk::{gdk, glib};
+use gtk::{gdk, gio, glib};
 use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::error;
 
 mod imp {
     use super::*;
@@ -42,6 +45,25 @@ mod imp {
             // Create header bar
             let header_bar = adw::HeaderBar::new();
             header_bar.set_title_widget(Some(&adw::WindowTitle::new(&gettext("Command Log"), "")));
+
+            // Runtime toggle for the capture, shared with the preference
+            let capture_switch = gtk::Switch::new();
+            capture_switch.set_valign(gtk::Align::Center);
+            capture_switch.set_tooltip_text(Some(&gettext("Record executed commands")));
+            obj.root_store()
+                .settings()
+                .bind("command-log-capture", &capture_switch, "active")
+                .build();
+            header_bar.pack_start(&capture_switch);
+
+            let export_btn = gtk::Button::from_icon_name("document-save-symbolic");
+            export_btn.set_tooltip_text(Some(&gettext("Export Log")));
+            export_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.export_log()
+            ));
+            header_bar.pack_end(&export_btn);
             toolbar_view.add_top_bar(&header_bar);
 
             // Create main content
@@ -66,6 +88,33 @@ mod imp {
             description.add_css_class("dim-label");
             content_box.append(&description);
 
+            // Logged commands are the ones before wrapping, which may be confusing in Flatpak
+            if let Some(prefix) = obj.root_store().command_runner().command_prefix() {
+                let prefix_label = gtk::Label::new(Some(&gettext_f!(
+                    "Commands run on the host, prefixed with: {prefix}",
+                    "prefix" => prefix
+                )));
+                prefix_label.set_wrap(true);
+                prefix_label.set_xalign(0.0);
+                prefix_label.set_selectable(true);
+                prefix_label.add_css_class("accent");
+                content_box.append(&prefix_label);
+            }
+
+            let disabled_label = gtk::Label::new(Some(&gettext(
+                "Command capture is disabled. New commands won't appear here until it is enabled again.",
+            )));
+            disabled_label.set_wrap(true);
+            disabled_label.set_xalign(0.0);
+            disabled_label.add_css_class("warning");
+            obj.root_store()
+                .settings()
+                .bind("command-log-capture", &disabled_label, "visible")
+                .invert_boolean()
+                .get_only()
+                .build();
+            content_box.append(&disabled_label);
+
             // Create list box to show all commands
             self.list_box.set_selection_mode(gtk::SelectionMode::None);
             content_box.append(&self.list_box);
@@ -102,8 +151,8 @@ impl CommandLogDialog {
     fn populate_command_list(&self) {
         let list_box = &self.imp().list_box;
 
-        // Get command events from output tracker
-        let command_events = self.root_store().command_runner().output_tracker().items();
+        // Get the captured command events, without turning the capture on
+        let command_events = self.root_store().command_runner().output_events();
 
         for event in command_events {
             let row = self.build_event_row(&event);
@@ -111,20 +160,60 @@ impl CommandLogDialog {
         }
     }
 
+    /// Saves every captured event to a file chosen by the user.
+    /// A `.json` file gets the JSON format, anything else gets plain text.
+    fn export_log(&self) {
+        let file_dialog = gtk::FileDialog::builder()
+            .title(gettext("Export Command Log"))
+            .initial_name("distroshelf-command-log.txt")
+            .modal(true)
+            .build();
+
+        let parent = self.root().and_downcast::<gtk::Window>();
+        file_dialog.save(
+            parent.as_ref(),
+            None::<&gio::Cancellable>,
+            glib::clone!(
+                #[weak(rename_to=this)]
+                self,
+                move |res| {
+                    let Some(path) = res.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    let events = this.root_store().command_runner().timed_output_events();
+                    let contents = if path.extension().is_some_and(|ext| ext == "json") {
+                        serde_json::to_string_pretty(&command_log_json(&events))
+                            .unwrap_or_default()
+                    } else {
+                        format_command_log(&events)
+                    };
+                    let message = match std::fs::write(&path, contents) {
+                        Ok(()) => gettext("Command log exported"),
+                        Err(e) => {
+                            error!(error = %e, path = %path.display(), "Failed to export the command log");
+                            gettext("Failed to export the command log")
+                        }
+                    };
+                    this.imp().toast_overlay.add_toast(adw::Toast::new(&message));
+                }
+            ),
+        );
+    }
+
     fn build_event_row(&self, event: &CommandRunnerEvent) -> gtk::ListBoxRow {
         let toast_overlay = &self.imp().toast_overlay;
 
         match event {
             CommandRunnerEvent::Spawned(id, command) => self.build_command_row(
                 &format!("Spawned [{}]", id),
-                &command.to_string(),
+                command,
                 "media-playback-start-symbolic",
                 "spawned",
                 toast_overlay,
             ),
             CommandRunnerEvent::Started(id, command) => self.build_command_row(
                 &format!("Started [{}]", id),
-                &command.to_string(),
+                command,
                 "system-run-symbolic",
                 "started",
                 toast_overlay,
@@ -146,7 +235,7 @@ impl CommandLogDialog {
     fn build_command_row(
         &self,
         title: &str,
-        command_str: &str,
+        command: &Command,
         icon_name: &str,
         css_class: &str,
         toast_overlay: &adw::ToastOverlay,
@@ -167,7 +256,17 @@ impl CommandLogDialog {
         title_label.set_xalign(0.0);
         title_label.add_css_class("caption");
 
-        let subtitle_label = gtk::Label::new(Some(command_str));
+        let command_str = command.to_string();
+        let subtitle_label = gtk::Label::new(Some(&command_str));
+        // Shows the command as it was actually run, e.g. through flatpak-spawn
+        let effective_command = self
+            .root_store()
+            .command_runner()
+            .wrap_command(command.clone())
+            .to_string();
+        if effective_command != command_str {
+            subtitle_label.set_tooltip_text(Some(&effective_command));
+        }
         subtitle_label.set_xalign(0.0);
         subtitle_label.add_css_class("caption");
         subtitle_label.add_css_class("dim-label");
@@ -181,7 +280,6 @@ impl CommandLogDialog {
         row.set_child(Some(&row_box));
 
         // Add click handler to copy command to clipboard
-        let command_owned = command_str.to_string();
         let gesture = gtk::GestureClick::new();
         gesture.connect_pressed(glib::clone!(
             #[weak]
@@ -189,7 +287,7 @@ impl CommandLogDialog {
             move |_, _, _, _| {
                 if let Some(display) = gdk::Display::default() {
                     let clipboard = display.clipboard();
-                    clipboard.set_text(&command_owned);
+                    clipboard.set_text(&command_str);
                     toast_overlay
                         .add_toast(adw::Toast::new(&gettext("Command copied to clipboard")));
                 }
@@ -223,3 +321,108 @@ impl CommandLogDialog {
         row
     }
 }
+
+fn format_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    glib::DateTime::from_unix_utc(secs)
+        .and_then(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC"))
+        .map(|s| s.to_string())
+        .unwrap_or_default()
+}
+
+// Kind of event and, for command events, the shell-quoted command
+fn describe_event(event: &CommandRunnerEvent) -> (&'static str, Option<String>) {
+    match event {
+        CommandRunnerEvent::Spawned(_, command) => ("spawned", Some(command.to_shell_string())),
+        CommandRunnerEvent::Started(_, command) => ("started", Some(command.to_shell_string())),
+        CommandRunnerEvent::Output(_, Ok(())) => ("completed", None),
+        CommandRunnerEvent::Output(_, Err(())) => ("failed", None),
+    }
+}
+
+/// One line per event: time, event id, kind and command
+fn format_command_log(events: &[(SystemTime, CommandRunnerEvent)]) -> String {
+    let mut out = String::new();
+    for (time, event) in events {
+        let (kind, command) = describe_event(event);
+        out.push_str(&format!(
+            "{} [{}] {}",
+            format_timestamp(*time),
+            event.event_id(),
+            kind
+        ));
+        if let Some(command) = command {
+            out.push_str(": ");
+            out.push_str(&command);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn command_log_json(events: &[(SystemTime, CommandRunnerEvent)]) -> serde_json::Value {
+    events
+        .iter()
+        .map(|(time, event)| {
+            let (kind, command) = describe_event(event);
+            serde_json::json!({
+                "time": format_timestamp(*time),
+                "id": event.event_id(),
+                "event": kind,
+                "command": command,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn mixed_events() -> Vec<(SystemTime, CommandRunnerEvent)> {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        vec![
+            (
+                time,
+                CommandRunnerEvent::Spawned(
+                    0,
+                    Command::new_with_args("distrobox", ["enter", "my box", "--", "echo", "it's"]),
+                ),
+            ),
+            (
+                time,
+                CommandRunnerEvent::Started(1, Command::new_with_args("podman", ["ps"])),
+            ),
+            (time, CommandRunnerEvent::Output(1, Ok(()))),
+            (time, CommandRunnerEvent::Output(2, Err(()))),
+        ]
+    }
+
+    #[test]
+    fn format_command_log_quotes_commands() {
+        assert_eq!(
+            format_command_log(&mixed_events()),
+            concat!(
+                "2023-11-14 22:13:20 UTC [0] spawned: distrobox enter 'my box' -- echo 'it'\\''s'\n",
+                "2023-11-14 22:13:20 UTC [1] started: podman ps\n",
+                "2023-11-14 22:13:20 UTC [1] completed\n",
+                "2023-11-14 22:13:20 UTC [2] failed\n",
+            )
+        );
+    }
+
+    #[test]
+    fn command_log_json_lists_every_event() {
+        let json = command_log_json(&mixed_events());
+        assert_eq!(json.as_array().unwrap().len(), 4);
+        assert_eq!(json[1]["event"], "started");
+        assert_eq!(json[1]["command"], "podman ps");
+        assert_eq!(json[3]["event"], "failed");
+        assert!(json[3]["command"].is_null());
+    }
+}
@@ -3,19 +3,27 @@ use adw::subclass::prelude::*;
 use gtk::gio::File;
 use gtk::{gio, glib};
 use std::time::Duration;
+use tracing::error;
 
-use crate::backends::{self, CreateArgName, CreateArgs, CreateArgsImage};
-use crate::dialogs::create_distrobox_helpers::split_repo_tag_digest;
+use crate::backends::assemble::validate_assemble;
+use crate::backends::{self, ContainerManager, CreateArgName, CreateArgs, CreateArgsImage};
+use crate::dialogs::create_distrobox_helpers::{derive_home_path, restore_create_dialog_tab};
 use crate::fakers::Command;
 use crate::i18n::gettext;
-use crate::models::Container;
+use crate::models::flag_presets::{builtin_presets, user_presets};
+use crate::models::image_reference::split_repo_tag_digest;
+use crate::models::known_distros::{CatalogImage, DistroFamily, group_images_by_family};
+use crate::models::{Container, FlagPreset};
 use crate::query::Query;
 use crate::root_store::RootStore;
 use crate::widgets::{ImageRowItem, SidebarRow};
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
 use glib::clone;
 use gtk::glib::{Properties, derived_properties};
@@ -150,6 +158,8 @@ mod imp {
         pub home_row_expander: adw::ExpanderRow,
         #[property(get, set, nullable)]
         pub home_folder: RefCell<Option<String>>,
+        // Set once the user picks a home, to stop deriving it from the name
+        pub home_manually_selected: Cell<bool>,
         #[property(get, set, nullable)]
         pub assemble_file: RefCell<Option<String>>,
         #[property(get, set, nullable)]
@@ -159,7 +169,14 @@ mod imp {
         pub nvidia_row: adw::SwitchRow,
         pub init_row: adw::SwitchRow,
         pub hostname_row: adw::EntryRow,
+        pub target_runtime_row: adw::ComboRow,
+        pub preset_toggles: RefCell<Vec<(FlagPreset, gtk::ToggleButton)>>,
         pub volume_rows: Rc<RefCell<Vec<adw::EntryRow>>>,
+        pub volumes_group: adw::PreferencesGroup,
+        // Picks a container whose volumes are added to the new one
+        pub copy_volumes_row: adw::ComboRow,
+        // The volume rows added from that container, replaced when another one is picked
+        pub copied_volume_rows: RefCell<Vec<adw::EntryRow>>,
         pub scrolled_window: gtk::ScrolledWindow,
         #[property(get, set, nullable, construct_only)]
         pub clone_src: RefCell<Option<Container>>,
@@ -298,6 +315,22 @@ impl CreateDistroboxDialog {
 
         this.root_store().downloaded_images_query().refetch();
 
+        // Cloning always uses the guided page, so only reopen the last tab otherwise
+        if this.clone_src().is_none() {
+            let settings = this.root_store().settings();
+            let tab = restore_create_dialog_tab(&settings.string("create-dialog-tab"));
+            this.imp().view_stack.set_visible_child_name(tab);
+            this.imp()
+                .view_stack
+                .connect_visible_child_name_notify(move |stack| {
+                    if let Some(name) = stack.visible_child_name()
+                        && let Err(e) = settings.set_string("create-dialog-tab", &name)
+                    {
+                        error!(error = %e, "Failed to save the create dialog tab");
+                    }
+                });
+        }
+
         this
     }
 
@@ -314,8 +347,26 @@ impl CreateDistroboxDialog {
         match active_view.as_str() {
             "assemble-file" => {
                 errors.reset_assemble_file();
-                if self.assemble_file().is_none() {
-                    errors.add_assemble_file_error(gettext("No file selected"));
+                match self.assemble_file() {
+                    None => errors.add_assemble_file_error(gettext("No file selected")),
+                    Some(path) => match std::fs::read_to_string(path.as_str()) {
+                        Ok(content) => {
+                            let problems = validate_assemble(&content);
+                            if !problems.is_empty() {
+                                errors.add_assemble_file_error(
+                                    problems
+                                        .iter()
+                                        .map(|e| e.to_string())
+                                        .collect::<Vec<_>>()
+                                        .join("\n"),
+                                );
+                            }
+                        }
+                        Err(e) => errors.add_assemble_file_error(format!(
+                            "{}: {e}",
+                            gettext("Cannot read the file")
+                        )),
+                    },
                 }
             }
             "assemble-url" => {
@@ -415,6 +466,7 @@ impl CreateDistroboxDialog {
             FileRowSelection::Folder,
             None, // No filter for folders
             move |path| {
+                this.imp().home_manually_selected.set(true);
                 this.set_home_folder(Some(path.display().to_string()));
             },
             clone!(
@@ -431,6 +483,32 @@ impl CreateDistroboxDialog {
         imp.home_row_expander.set_show_enable_switch(true);
         imp.home_row_expander.set_enable_expansion(false);
         imp.home_row_expander.add_row(&home_row);
+
+        // Derive the home from the name, as "<base>/<name>", when the user prefers a custom home
+        if self.root_store().settings().boolean("default-custom-home")
+            && imp.clone_src.borrow().is_none()
+        {
+            imp.home_row_expander.set_enable_expansion(true);
+            imp.name_row.connect_changed(clone!(
+                #[weak(rename_to=this)]
+                self,
+                #[weak]
+                home_row,
+                move |entry| {
+                    let imp = this.imp();
+                    if !imp.home_row_expander.enables_expansion()
+                        || imp.home_manually_selected.get()
+                    {
+                        return;
+                    }
+                    let base = this.root_store().settings().string("custom-home-base");
+                    let home = derive_home_path(&base, &entry.text(), &glib::home_dir());
+                    this.set_home_folder(home.map(|p| p.display().to_string()));
+                    home_row.set_subtitle(this.home_folder().as_deref().unwrap_or(""));
+                }
+            ));
+        }
+
         imp.home_row_expander
             .connect_enable_expansion_notify(clone!(
                 #[weak(rename_to=this)]
@@ -459,9 +537,21 @@ impl CreateDistroboxDialog {
         advanced_group.add(&imp.nvidia_row);
         advanced_group.add(&imp.init_row);
 
+        // A clone can be moved to another container runtime
+        if imp.clone_src.borrow().is_some() {
+            imp.target_runtime_row.set_title(&gettext("Target Runtime"));
+            imp.target_runtime_row.set_subtitle(&gettext(
+                "A clone in another runtime isn't listed here, only the containers of the detected one are",
+            ));
+            let model = gtk::StringList::new(&[&gettext("Same as Source"), "Podman", "Docker"]);
+            imp.target_runtime_row.set_model(Some(&model));
+            advanced_group.add(&imp.target_runtime_row);
+        }
+
         let volumes_group = self.build_volumes_group();
         content.append(&preferences_group);
         content.append(&advanced_group);
+        content.append(&self.build_presets_group());
         content.append(&volumes_group);
 
         let create_btn = self.build_create_btn(&self.imp().create_guided_btn);
@@ -960,7 +1050,34 @@ impl CreateDistroboxDialog {
         scrolled_window.set_child(Some(&content_box));
         scrolled_window.set_vexpand(true);
 
-        view.set_content(Some(&scrolled_window));
+        // The same images, described and grouped by distro family
+        let catalog_box = gtk::Box::new(gtk::Orientation::Vertical, 24);
+        catalog_box.set_margin_start(12);
+        catalog_box.set_margin_end(12);
+        catalog_box.set_margin_top(12);
+        catalog_box.set_margin_bottom(12);
+        let catalog_window = gtk::ScrolledWindow::new();
+        catalog_window.set_child(Some(&adw::Clamp::builder().child(&catalog_box).build()));
+        catalog_window.set_vexpand(true);
+
+        let view_stack = adw::ViewStack::new();
+        view_stack.add_titled_with_icon(
+            &catalog_window,
+            Some("catalog"),
+            &gettext("Catalog"),
+            "view-grid-symbolic",
+        );
+        view_stack.add_titled_with_icon(
+            &scrolled_window,
+            Some("all"),
+            &gettext("All Images"),
+            "view-list-symbolic",
+        );
+        let switcher_bar = adw::ViewSwitcherBar::new();
+        switcher_bar.set_stack(Some(&view_stack));
+        switcher_bar.set_reveal(true);
+        view.add_bottom_bar(&switcher_bar);
+        view.set_content(Some(&view_stack));
 
         // Update custom row
         search_entry.connect_search_changed(clone!(
@@ -993,6 +1110,77 @@ impl CreateDistroboxDialog {
             }
         );
 
+        let render_catalog = clone!(
+            #[weak(rename_to=this)]
+            self,
+            #[weak]
+            catalog_box,
+            #[weak]
+            search_entry,
+            #[strong]
+            handle_image_selected,
+            move || {
+                while let Some(child) = catalog_box.first_child() {
+                    catalog_box.remove(&child);
+                }
+                let images: Vec<String> = this
+                    .imp()
+                    .images_model
+                    .snapshot()
+                    .into_iter()
+                    .filter_map(|item| item.downcast::<gtk::StringObject>().ok())
+                    .map(|item| item.string().to_string())
+                    .collect();
+                let search = search_entry.text().to_lowercase();
+                for group in group_images_by_family(&images) {
+                    let images: Vec<CatalogImage> = group
+                        .images
+                        .into_iter()
+                        .filter(|image| {
+                            [
+                                Some(image.image.as_str()),
+                                Some(image.title.as_str()),
+                                image.description,
+                            ]
+                            .into_iter()
+                            .flatten()
+                            .any(|text| text.to_lowercase().contains(&search))
+                        })
+                        .collect();
+                    if images.is_empty() {
+                        continue;
+                    }
+                    let family_group = adw::PreferencesGroup::new();
+                    family_group.set_title(&family_title(group.family));
+                    for image in images {
+                        family_group.add(&build_catalog_row(&image, handle_image_selected.clone()));
+                    }
+                    catalog_box.append(&family_group);
+                }
+            }
+        );
+        render_catalog();
+        search_entry.connect_search_changed(clone!(
+            #[strong]
+            render_catalog,
+            move |_| render_catalog()
+        ));
+        // The picker is built each time it's opened, stop following the images once it's gone
+        let items_changed_handler = RefCell::new(Some(
+            self.imp()
+                .images_model
+                .connect_items_changed(move |_, _, _, _| render_catalog()),
+        ));
+        catalog_box.connect_destroy(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                if let Some(handler) = items_changed_handler.take() {
+                    this.imp().images_model.disconnect(handler);
+                }
+            }
+        ));
+
         // Handle Enter key on search_entry to select first filtered image
         search_entry.connect_activate(clone!(
             #[strong]
@@ -1133,7 +1321,7 @@ impl CreateDistroboxDialog {
         if let Some(name) = name
             && !image_error
         {
-            Ok(CreateArgs {
+            let mut create_args = CreateArgs {
                 name,
                 image,
                 nvidia: imp.nvidia_row.is_active(),
@@ -1146,73 +1334,349 @@ impl CreateDistroboxDialog {
                     .settings()
                     .boolean("distrobox-create-no-entry"),
                 volumes,
-            })
+                container_manager: self.target_container_manager(),
+                ..Default::default()
+            };
+            for (preset, toggle) in imp.preset_toggles.borrow().iter() {
+                if toggle.is_active()
+                    && let Err(e) = preset.apply(&mut create_args)
+                {
+                    error!(preset = %preset.name, error = %e.hint, "Invalid flag preset");
+                }
+            }
+            Ok(create_args)
         } else {
             Err(())
         }
     }
 
+    fn target_container_manager(&self) -> Option<ContainerManager> {
+        if self.imp().clone_src.borrow().is_none() {
+            return None;
+        }
+        match self.imp().target_runtime_row.selected() {
+            1 => Some(ContainerManager::Podman),
+            2 => Some(ContainerManager::Docker),
+            _ => None,
+        }
+    }
+
+    /// Toggles for the built-in and user defined flag presets
+    fn build_presets_group(&self) -> adw::PreferencesGroup {
+        let presets_group = adw::PreferencesGroup::new();
+        presets_group.set_title(&gettext("Presets"));
+        presets_group.set_description(Some(&gettext(
+            "Extra flags and volumes for common setups. Add your own with the flag-presets setting.",
+        )));
+
+        let flow_box = gtk::FlowBox::new();
+        flow_box.set_selection_mode(gtk::SelectionMode::None);
+        flow_box.set_column_spacing(6);
+        flow_box.set_row_spacing(6);
+        flow_box.set_max_children_per_line(4);
+
+        let custom_presets: HashMap<String, String> = self
+            .root_store()
+            .settings()
+            .value("flag-presets")
+            .get()
+            .unwrap_or_default();
+        let mut toggles = vec![];
+        let presets = builtin_presets()
+            .into_iter()
+            .map(|preset| (gettext(&preset.name), preset))
+            .chain(
+                user_presets(&custom_presets)
+                    .into_iter()
+                    .map(|preset| (preset.name.clone(), preset)),
+            );
+        for (label, preset) in presets {
+            let toggle = gtk::ToggleButton::with_label(&label);
+            toggle.add_css_class("pill");
+            let details: Vec<&str> = preset
+                .additional_flags
+                .iter()
+                .chain(&preset.volumes)
+                .map(|s| s.as_str())
+                .collect();
+            toggle.set_tooltip_text(Some(&details.join(" ")));
+            flow_box.append(&toggle);
+            toggles.push((preset, toggle));
+        }
+        *self.imp().preset_toggles.borrow_mut() = toggles;
+
+        presets_group.add(&flow_box);
+        presets_group
+    }
+
     pub fn build_volumes_group(&self) -> adw::PreferencesGroup {
-        let volumes_group = adw::PreferencesGroup::new();
+        let volumes_group = self.imp().volumes_group.clone();
         volumes_group.set_title(&gettext("Volumes"));
         volumes_group.set_description(Some(&gettext(
             "Specify volumes in the format 'host_path:container_path'",
         )));
 
+        let copy_volumes_row = self.imp().copy_volumes_row.clone();
+        copy_volumes_row.set_title(&gettext("Copy Volumes From"));
+        let mut names = vec![gettext("None")];
+        names.extend(self.root_store().containers().iter().map(|c| c.name()));
+        let names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+        copy_volumes_row.set_model(Some(&gtk::StringList::new(&names)));
+        copy_volumes_row.connect_selected_notify(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |row| {
+                for copied_row in this.imp().copied_volume_rows.take() {
+                    this.remove_volume_row(&copied_row);
+                }
+                let selected = row.selected();
+                let Some(name) = row
+                    .selected_item()
+                    .and_downcast::<gtk::StringObject>()
+                    .filter(|_| selected > 0)
+                else {
+                    return;
+                };
+                let Some((_, container)) = this
+                    .root_store()
+                    .containers()
+                    .find_with(|c| c.name() == name.string())
+                else {
+                    return;
+                };
+                glib::MainContext::ref_thread_default().spawn_local(async move {
+                    let volumes = this.root_store().container_user_volumes(&container).await;
+                    // Another container was picked in the meantime
+                    if this.imp().copy_volumes_row.selected() != selected {
+                        return;
+                    }
+                    match volumes {
+                        Ok(volumes) => {
+                            let rows = volumes
+                                .iter()
+                                .map(|volume| this.add_volume_row(&volume.to_string()))
+                                .collect();
+                            this.imp().copied_volume_rows.replace(rows);
+                        }
+                        Err(e) => {
+                            error!(
+                                error = %e,
+                                container = %container.name(),
+                                "Failed to read volumes"
+                            );
+                            this.imp().toast_overlay.add_toast(adw::Toast::new(&gettext(
+                                "Failed to read the volumes of the container",
+                            )));
+                        }
+                    }
+                });
+            }
+        ));
+        volumes_group.add(&copy_volumes_row);
+
         let add_volume_button = adw::ButtonRow::builder()
             .title(gettext("Add Volume"))
             .build();
         add_volume_button.connect_activated(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                this.add_volume_row("");
+            }
+        ));
+
+        volumes_group.add(&add_volume_button);
+
+        volumes_group
+    }
+
+    fn add_volume_row(&self, text: &str) -> adw::EntryRow {
+        let volumes_group = self.imp().volumes_group.clone();
+        let volume_row = adw::EntryRow::new();
+        volume_row.set_title(&gettext("Volume"));
+        volume_row.set_text(text);
+        volume_row.connect_changed(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                this.imp().errors_query.refetch();
+            }
+        ));
+
+        let remove_button = gtk::Button::from_icon_name("user-trash-symbolic");
+        remove_button.set_tooltip_text(Some(&gettext("Remove Volume")));
+        remove_button.add_css_class("flat");
+        remove_button.set_valign(gtk::Align::Center);
+        remove_button.add_css_class("destructive-action");
+        remove_button.connect_clicked(clone!(
             #[weak(rename_to=this)]
             self,
             #[weak]
-            volumes_group,
+            volume_row,
             move |_| {
-                let volume_row = adw::EntryRow::new();
-                volume_row.set_title(&gettext("Volume"));
-                volume_row.connect_changed(clone!(
-                    #[weak(rename_to=this)]
-                    this,
-                    move |_| {
-                        this.imp().errors_query.refetch();
-                    }
-                ));
-
-                let remove_button = gtk::Button::from_icon_name("user-trash-symbolic");
-                remove_button.set_tooltip_text(Some(&gettext("Remove Volume")));
-                remove_button.add_css_class("flat");
-                remove_button.set_valign(gtk::Align::Center);
-                remove_button.add_css_class("destructive-action");
-                remove_button.connect_clicked(clone!(
-                    #[weak]
-                    this,
-                    #[weak]
-                    volumes_group,
-                    #[weak]
-                    volume_row,
-                    move |_| {
-                        this.imp()
-                            .volume_rows
-                            .borrow_mut()
-                            .retain(|row| row != &volume_row);
-                        volumes_group.remove(&volume_row);
-                        this.imp().errors_query.refetch();
+                this.remove_volume_row(&volume_row);
+            }
+        ));
+        volume_row.add_suffix(&self.build_volume_options_button(&volume_row));
+        volume_row.add_suffix(&remove_button);
+
+        self.imp().volume_rows.borrow_mut().push(volume_row.clone());
+        volumes_group.add(&volume_row);
+        self.imp().errors_query.refetch();
+        volume_row
+    }
+
+    fn remove_volume_row(&self, volume_row: &adw::EntryRow) {
+        let imp = self.imp();
+        let mut volume_rows = imp.volume_rows.borrow_mut();
+        // The row may have been removed by hand already
+        let Some(position) = volume_rows.iter().position(|row| row == volume_row) else {
+            return;
+        };
+        volume_rows.remove(position);
+        drop(volume_rows);
+        imp.volumes_group.remove(volume_row);
+        imp.errors_query.refetch();
+    }
+
+    /// Menu toggling the read-only and SELinux relabel options of a volume, rewriting its text
+    fn build_volume_options_button(&self, volume_row: &adw::EntryRow) -> gtk::MenuButton {
+        let read_only_check = gtk::CheckButton::with_label(&gettext("Read-Only"));
+        let no_relabel_check = gtk::CheckButton::with_label(&gettext("Keep SELinux Label"));
+        let shared_check = gtk::CheckButton::with_label(&gettext("Shared SELinux Label (z)"));
+        shared_check.set_group(Some(&no_relabel_check));
+        let private_check = gtk::CheckButton::with_label(&gettext("Private SELinux Label (Z)"));
+        private_check.set_group(Some(&no_relabel_check));
+
+        let options_box = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        options_box.append(&read_only_check);
+        options_box.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+        options_box.append(&no_relabel_check);
+        options_box.append(&shared_check);
+        options_box.append(&private_check);
+        let popover = gtk::Popover::new();
+        popover.set_child(Some(&options_box));
+
+        // Set while the checks are updated from the text, to not write it back half updated
+        let syncing = Rc::new(Cell::new(false));
+        let apply = clone!(
+            #[weak]
+            volume_row,
+            #[weak]
+            read_only_check,
+            #[weak]
+            shared_check,
+            #[weak]
+            private_check,
+            #[strong]
+            syncing,
+            move || {
+                if syncing.get() {
+                    return;
+                }
+                let Ok(volume) = volume_row.text().parse::<backends::Volume>() else {
+                    return;
+                };
+                let relabel = if shared_check.is_active() {
+                    Some(backends::VolumeRelabel::Shared)
+                } else if private_check.is_active() {
+                    Some(backends::VolumeRelabel::Private)
+                } else {
+                    None
+                };
+                let updated = volume
+                    .clone()
+                    .with_read_only(read_only_check.is_active())
+                    .with_relabel(relabel);
+                if updated != volume {
+                    volume_row.set_text(&updated.to_string());
+                }
+            }
+        );
+        read_only_check.connect_toggled(clone!(
+            #[strong]
+            apply,
+            move |_| apply()
+        ));
+        for check in [&no_relabel_check, &shared_check, &private_check] {
+            // The previously active check of the group gets toggled too, skip it
+            check.connect_toggled(clone!(
+                #[strong]
+                apply,
+                move |check| {
+                    if check.is_active() {
+                        apply();
                     }
-                ));
-                volume_row.add_suffix(&remove_button);
+                }
+            ));
+        }
 
-                this.imp().volume_rows.borrow_mut().push(volume_row.clone());
-                volumes_group.add(&volume_row);
-                this.imp().errors_query.refetch();
+        popover.connect_show(clone!(
+            #[weak]
+            volume_row,
+            #[weak]
+            options_box,
+            #[weak]
+            read_only_check,
+            #[weak]
+            no_relabel_check,
+            #[weak]
+            shared_check,
+            #[weak]
+            private_check,
+            move |_| {
+                let volume = volume_row.text().parse::<backends::Volume>().ok();
+                options_box.set_sensitive(volume.is_some());
+                let Some(volume) = volume else {
+                    return;
+                };
+                syncing.set(true);
+                read_only_check.set_active(volume.mode.is_some());
+                match volume.relabel {
+                    Some(backends::VolumeRelabel::Shared) => shared_check.set_active(true),
+                    Some(backends::VolumeRelabel::Private) => private_check.set_active(true),
+                    None => no_relabel_check.set_active(true),
+                }
+                syncing.set(false);
             }
         ));
 
-        volumes_group.add(&add_volume_button);
+        let options_button = gtk::MenuButton::new();
+        options_button.set_icon_name("view-more-symbolic");
+        options_button.set_tooltip_text(Some(&gettext("Volume Options")));
+        options_button.add_css_class("flat");
+        options_button.set_valign(gtk::Align::Center);
+        options_button.set_popover(Some(&popover));
+        options_button
+    }
 
-        volumes_group
+    /// Adds the volumes of `container` to the new container
+    pub fn copy_volumes_from(&self, container: &Container) {
+        let row = &self.imp().copy_volumes_row;
+        let Some(model) = row.model() else {
+            return;
+        };
+        let position = (1..model.n_items()).find(|&i| {
+            model
+                .item(i)
+                .and_downcast::<gtk::StringObject>()
+                .is_some_and(|s| s.string() == container.name())
+        });
+        if let Some(position) = position {
+            row.set_selected(position);
+        }
+    }
+
+    /// Selects `image` as the base image, as if picked from the image list
+    pub fn select_image(&self, image: &str) {
+        self.imp().selected_image.replace(image.to_string());
+        self.imp().image_row.set_subtitle(image);
     }
 
     async fn download_ini_file(&self, url: &str) -> anyhow::Result<String> {
+        if self.root_store().offline_mode() {
+            anyhow::bail!("Offline mode is enabled, the file can't be downloaded");
+        }
         // Download the .ini file content using curl
         // CRITICAL: Use self.root_store().command_runner() for Flatpak compatibility
         let command_runner = self.root_store().command_runner();
@@ -1242,3 +1706,29 @@ impl CreateDistroboxDialog {
         Ok(content)
     }
 }
+
+fn family_title(family: DistroFamily) -> String {
+    match family {
+        DistroFamily::Debian => gettext("Debian Based"),
+        DistroFamily::Rpm => gettext("RPM Based"),
+        DistroFamily::Arch => gettext("Arch Based"),
+        DistroFamily::Alpine => gettext("Alpine"),
+        DistroFamily::Independent => gettext("Independent"),
+        DistroFamily::Other => gettext("Other"),
+    }
+}
+
+fn build_catalog_row(image: &CatalogImage, on_selected: impl Fn(&str) + 'static) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_use_markup(false);
+    row.set_title(&image.title);
+    if let Some(description) = image.description {
+        row.set_subtitle(&gettext(description));
+        row.set_tooltip_text(Some(&image.image));
+    }
+    row.set_activatable(true);
+    row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+    let image = image.image.clone();
+    row.connect_activated(move |_| on_selected(&image));
+    row
+}
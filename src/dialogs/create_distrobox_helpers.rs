@@ -1,5 +1,8 @@
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
+use crate::models::image_reference::split_repo_tag_digest;
+
 // Helper for deriving image prefill suggestions from a container name.
 use regex::Regex;
 
@@ -24,29 +27,6 @@ fn compare_version_vec(a: &[u64], b: &[u64]) -> std::cmp::Ordering {
     a.len().cmp(&b.len())
 }
 
-pub fn split_repo_tag_digest(s: &str) -> (&str, Option<&str>, Option<&str>) {
-    // Return (repo, tag_opt, digest_opt)
-    let last_slash = s.rfind('/');
-    if let Some(at_pos) = s.rfind('@')
-        && last_slash.is_none_or(|ls| at_pos > ls)
-    {
-        // If there is a tag before the @ (colon after last slash), strip it from repo
-        let before_at = &s[..at_pos];
-        if let Some(col_pos) = before_at.rfind(':')
-            && last_slash.is_none_or(|ls| col_pos > ls)
-        {
-            return (&before_at[..col_pos], None, Some(&s[at_pos + 1..]));
-        }
-        return (before_at, None, Some(&s[at_pos + 1..]));
-    }
-    if let Some(col_pos) = s.rfind(':')
-        && last_slash.is_none_or(|ls| col_pos > ls)
-    {
-        return (&s[..col_pos], Some(&s[col_pos + 1..]), None);
-    }
-    (s, None, None)
-}
-
 pub fn derive_image_prefill(
     container_name: &str,
     candidates: Option<&[String]>,
@@ -224,32 +204,61 @@ pub fn derive_image_prefill(
     (filter, Some(suggested))
 }
 
+/// Derives the custom home of a new container as `<base>/<name>`, expanding a leading `~` to `home`.
+pub fn derive_home_path(base: &str, container_name: &str, home: &Path) -> Option<PathBuf> {
+    let base = base.trim();
+    let name = container_name.trim();
+    if base.is_empty() || name.is_empty() {
+        return None;
+    }
+    let base = if base == "~" {
+        home.to_path_buf()
+    } else if let Some(rest) = base.strip_prefix("~/") {
+        home.join(rest)
+    } else {
+        PathBuf::from(base)
+    };
+    Some(base.join(name))
+}
+
+/// Names of the create dialog tabs, the first one being the default
+pub const CREATE_DIALOG_TABS: [&str; 3] = ["create", "assemble-file", "assemble-url"];
+
+/// Returns the tab to open for the saved `create-dialog-tab` setting, falling back to the default one.
+pub fn restore_create_dialog_tab(saved: &str) -> &'static str {
+    CREATE_DIALOG_TABS
+        .iter()
+        .find(|tab| **tab == saved.trim())
+        .copied()
+        .unwrap_or(CREATE_DIALOG_TABS[0])
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{derive_image_prefill, split_repo_tag_digest};
+    use std::path::{Path, PathBuf};
+
+    use super::{
+        CREATE_DIALOG_TABS, derive_home_path, derive_image_prefill, restore_create_dialog_tab,
+        split_repo_tag_digest,
+    };
 
     #[test]
-    fn split_repo_tag_digest_examples() {
-        assert_eq!(
-            split_repo_tag_digest("repo:1.2.3"),
-            ("repo", Some("1.2.3"), None)
-        );
+    fn derive_home_path_examples() {
+        let home = Path::new("/home/me");
         assert_eq!(
-            split_repo_tag_digest("repo@sha256:abcdef"),
-            ("repo", None, Some("sha256:abcdef"))
+            derive_home_path("~/distrobox-homes", "ubuntu", home),
+            Some(PathBuf::from("/home/me/distrobox-homes/ubuntu"))
         );
         assert_eq!(
-            split_repo_tag_digest("host:5000/repo:1.0"),
-            ("host:5000/repo", Some("1.0"), None)
+            derive_home_path("/data/homes/", "arch", home),
+            Some(PathBuf::from("/data/homes/arch"))
         );
         assert_eq!(
-            split_repo_tag_digest("host:5000/repo@sha256:abc"),
-            ("host:5000/repo", None, Some("sha256:abc"))
-        );
-        assert_eq!(
-            split_repo_tag_digest("repo:tag@sha256:abc"),
-            ("repo", None, Some("sha256:abc"))
+            derive_home_path("~", "fedora", home),
+            Some(PathBuf::from("/home/me/fedora"))
         );
+        assert_eq!(derive_home_path("~/distrobox-homes", " ", home), None);
+        assert_eq!(derive_home_path("", "ubuntu", home), None);
     }
 
     #[test]
@@ -306,25 +315,6 @@ mod tests {
         assert!(s.is_none());
     }
 
-    #[test]
-    fn split_repo_tag_digest_more_cases() {
-        // triple combo: repo:tag@sha256: -> digest should win, tag discarded
-        assert_eq!(
-            split_repo_tag_digest("repo:tag@sha256:abc"),
-            ("repo", None, Some("sha256:abc"))
-        );
-        // host with port, tag and digest -> digest wins and repo includes host:port/repo
-        assert_eq!(
-            split_repo_tag_digest("example.com:5000/repo:1.0@sha256:abc"),
-            ("example.com:5000/repo", None, Some("sha256:abc"))
-        );
-        // plain repo no tag/digest
-        assert_eq!(
-            split_repo_tag_digest("plainrepo"),
-            ("plainrepo", None, None)
-        );
-    }
-
     #[test]
     fn normalization_preserves_registry_port_and_collapses_dashes() {
         let (f, s) = derive_image_prefill("Host:5000/Repo", None);
@@ -377,4 +367,13 @@ mod tests {
         assert_eq!(f, "repo");
         assert_eq!(s.unwrap(), "repo@sha256:deadbeef");
     }
+
+    #[test]
+    fn create_dialog_tab_round_trip() {
+        for tab in CREATE_DIALOG_TABS {
+            assert_eq!(restore_create_dialog_tab(tab), tab);
+        }
+        assert_eq!(restore_create_dialog_tab(""), "create");
+        assert_eq!(restore_create_dialog_tab("from-url"), "create");
+    }
 }
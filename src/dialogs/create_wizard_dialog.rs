@@ -0,0 +1,351 @@
+use crate::backends::{CreateArgs, CreateArgsImage, ExportableApp};
+use crate::i18n::gettext;
+use crate::models::image_reference::derive_container_name;
+use crate::models::{RootStore, WizardEvent, WizardStep};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{Properties, clone, derived_properties};
+use gtk::glib;
+use std::cell::RefCell;
+
+mod imp {
+    use super::*;
+
+    // Guides new users from an image to a container with its apps exported to the host
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::CreateWizardDialog)]
+    pub struct CreateWizardDialog {
+        #[property(get, set, construct)]
+        pub root_store: RefCell<RootStore>,
+        pub step: RefCell<WizardStep>,
+        pub container_name: RefCell<String>,
+        pub navigation_view: adw::NavigationView,
+        pub image_row: adw::EntryRow,
+        pub progress_page: adw::NavigationPage,
+        pub status_page: adw::StatusPage,
+        pub progress_bar: gtk::ProgressBar,
+        pub close_btn: gtk::Button,
+        pub apps_list: gtk::ListBox,
+        pub app_checks: RefCell<Vec<(String, gtk::CheckButton)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CreateWizardDialog {
+        const NAME: &'static str = "CreateWizardDialog";
+        type Type = super::CreateWizardDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    #[derived_properties]
+    impl ObjectImpl for CreateWizardDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title(&gettext("Create and Export"));
+            obj.set_content_width(480);
+            obj.set_content_height(560);
+
+            self.navigation_view.add(&obj.build_image_page());
+            self.navigation_view.add(&obj.build_progress_page());
+            self.navigation_view.add(&obj.build_apps_page());
+            obj.set_child(Some(&self.navigation_view));
+        }
+    }
+
+    impl WidgetImpl for CreateWizardDialog {}
+    impl AdwDialogImpl for CreateWizardDialog {}
+}
+
+glib::wrapper! {
+    pub struct CreateWizardDialog(ObjectSubclass<imp::CreateWizardDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl CreateWizardDialog {
+    pub fn new(root_store: RootStore) -> Self {
+        glib::Object::builder()
+            .property("root-store", root_store)
+            .build()
+    }
+
+    fn build_image_page(&self) -> adw::NavigationPage {
+        let imp = self.imp();
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let group = adw::PreferencesGroup::new();
+        group.set_title(&gettext("Pick an Image"));
+        group.set_description(Some(&gettext(
+            "The new container is created from this image, then you can add its apps to your app menu.",
+        )));
+        imp.image_row.set_title(&gettext("Image"));
+        imp.image_row.set_text("docker.io/library/ubuntu:latest");
+        imp.image_row
+            .connect_changed(|row| row.remove_css_class("error"));
+        group.add(&imp.image_row);
+
+        let create_btn = gtk::Button::with_label(&gettext("Create"));
+        create_btn.add_css_class("suggested-action");
+        create_btn.add_css_class("pill");
+        create_btn.set_halign(gtk::Align::Center);
+        create_btn.set_margin_top(12);
+        create_btn.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| this.start()
+        ));
+
+        let page = adw::PreferencesPage::new();
+        page.set_vexpand(true);
+        page.add(&group);
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        content.append(&page);
+        content.append(&create_btn);
+        content.set_margin_bottom(24);
+        toolbar_view.set_content(Some(&content));
+
+        adw::NavigationPage::builder()
+            .tag("image")
+            .title(gettext("Create and Export"))
+            .child(&toolbar_view)
+            .build()
+    }
+
+    fn build_progress_page(&self) -> adw::NavigationPage {
+        let imp = self.imp();
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        imp.progress_bar.set_show_text(false);
+        imp.close_btn.set_label(&gettext("Close"));
+        imp.close_btn.add_css_class("pill");
+        imp.close_btn.set_halign(gtk::Align::Center);
+        imp.close_btn.set_visible(false);
+        imp.close_btn.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                this.close();
+            }
+        ));
+
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        vbox.append(&imp.progress_bar);
+        vbox.append(&imp.close_btn);
+        imp.status_page.set_child(Some(&vbox));
+        toolbar_view.set_content(Some(&imp.status_page));
+
+        imp.progress_page.set_tag(Some("progress"));
+        imp.progress_page.set_title(&gettext("Create and Export"));
+        // Going back while a step runs would leave it running unseen
+        imp.progress_page.set_can_pop(false);
+        imp.progress_page.set_child(Some(&toolbar_view));
+        imp.progress_page.clone()
+    }
+
+    fn build_apps_page(&self) -> adw::NavigationPage {
+        let imp = self.imp();
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        imp.apps_list.add_css_class("boxed-list");
+        imp.apps_list.set_selection_mode(gtk::SelectionMode::None);
+        let group = adw::PreferencesGroup::new();
+        group.set_title(&gettext("Export Apps"));
+        group.set_description(Some(&gettext(
+            "Selected apps will appear in your app menu, like the ones installed on the host.",
+        )));
+        group.add(&imp.apps_list);
+
+        let export_btn = gtk::Button::with_label(&gettext("Export Selected"));
+        export_btn.add_css_class("suggested-action");
+        export_btn.add_css_class("pill");
+        export_btn.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| this.export_selected()
+        ));
+        let skip_btn = gtk::Button::with_label(&gettext("Skip"));
+        skip_btn.add_css_class("pill");
+        skip_btn.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| this.advance(WizardEvent::AppsSelected(0))
+        ));
+        let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        buttons.set_halign(gtk::Align::Center);
+        buttons.set_margin_top(12);
+        buttons.set_margin_bottom(24);
+        buttons.append(&skip_btn);
+        buttons.append(&export_btn);
+
+        let page = adw::PreferencesPage::new();
+        page.set_vexpand(true);
+        page.add(&group);
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        content.append(&page);
+        content.append(&buttons);
+        toolbar_view.set_content(Some(&content));
+
+        adw::NavigationPage::builder()
+            .tag("apps")
+            .title(gettext("Export Apps"))
+            .can_pop(false)
+            .child(&toolbar_view)
+            .build()
+    }
+
+    fn start(&self) {
+        let imp = self.imp();
+        let image = imp.image_row.text();
+        let (Some(name), Ok(image)) = (
+            derive_container_name(&image),
+            CreateArgsImage::new(image.trim()),
+        ) else {
+            imp.image_row.add_css_class("error");
+            return;
+        };
+        let name = self.root_store().available_container_name(name);
+        imp.container_name.replace(name.to_string());
+        imp.step.replace(WizardStep::Creating);
+        self.update_progress(&WizardStep::Creating);
+        imp.navigation_view.push_by_tag("progress");
+
+        let task = self.root_store().start_create_container(CreateArgs {
+            name,
+            image: Some(image),
+            ..Default::default()
+        });
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=this)]
+            self,
+            async move {
+                task.wait_ended().await;
+                let event = match task.error_message() {
+                    Some(error) => WizardEvent::Failed(error),
+                    None => WizardEvent::Succeeded,
+                };
+                this.advance(event);
+            }
+        ));
+    }
+
+    /// Moves the wizard to its next step, starting the work of that step
+    fn advance(&self, event: WizardEvent) {
+        let imp = self.imp();
+        let step = imp.step.take().transition(event);
+        imp.step.replace(step.clone());
+        self.update_progress(&step);
+
+        let name = imp.container_name.borrow().clone();
+        let root_store = self.root_store();
+        match step {
+            WizardStep::WaitingReady => {
+                self.spawn_step(async move { root_store.wait_container_ready(&name).await });
+            }
+            WizardStep::LoadingApps => {
+                let this = self.clone();
+                self.spawn_step(async move {
+                    let apps = root_store.distrobox().list_apps(&name).await?;
+                    this.show_apps(&apps);
+                    Ok(())
+                });
+            }
+            WizardStep::SelectingApps => {
+                imp.navigation_view.push_by_tag("apps");
+            }
+            WizardStep::Exporting => {
+                imp.navigation_view.pop_to_tag("progress");
+                let paths = imp
+                    .app_checks
+                    .borrow()
+                    .iter()
+                    .filter(|(_, check)| check.is_active())
+                    .map(|(path, _)| path.clone())
+                    .collect();
+                let task = root_store.export_apps_from(&name, paths);
+                self.spawn_step(async move {
+                    task.wait_ended().await;
+                    match task.error_message() {
+                        Some(error) => Err(anyhow::anyhow!(error)),
+                        None => Ok(()),
+                    }
+                });
+            }
+            WizardStep::Creating | WizardStep::Done | WizardStep::Failed(_) => {
+                imp.navigation_view.pop_to_tag("progress");
+            }
+        }
+    }
+
+    fn spawn_step(&self, step: impl Future<Output = anyhow::Result<()>> + 'static) {
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=this)]
+            self,
+            async move {
+                let event = match step.await {
+                    Ok(()) => WizardEvent::Succeeded,
+                    Err(e) => WizardEvent::Failed(e.to_string()),
+                };
+                this.advance(event);
+            }
+        ));
+    }
+
+    fn update_progress(&self, step: &WizardStep) {
+        let imp = self.imp();
+        let (icon, title) = match step {
+            WizardStep::Creating => (None, gettext("Creating Container")),
+            WizardStep::WaitingReady => (None, gettext("Setting Up Container")),
+            WizardStep::LoadingApps => (None, gettext("Looking for Apps")),
+            WizardStep::SelectingApps => (None, gettext("Choose Apps to Export")),
+            WizardStep::Exporting => (None, gettext("Exporting Apps")),
+            WizardStep::Done => (Some("object-select-symbolic"), gettext("All Set")),
+            WizardStep::Failed(_) => (
+                Some("dialog-error-symbolic"),
+                gettext("Something Went Wrong"),
+            ),
+        };
+        imp.status_page.set_icon_name(icon);
+        imp.status_page.set_title(&title);
+        let description = match step {
+            WizardStep::Creating => gettext("Downloading the image may take some time."),
+            WizardStep::Done => gettext("Your container is ready to use."),
+            WizardStep::Failed(error) => error.clone(),
+            _ => String::new(),
+        };
+        imp.status_page.set_description(Some(&description));
+        imp.progress_bar.set_fraction(step.progress());
+        imp.progress_bar.set_visible(step.is_busy());
+        imp.close_btn.set_visible(!step.is_busy());
+    }
+
+    fn show_apps(&self, apps: &[ExportableApp]) {
+        let imp = self.imp();
+        imp.apps_list.remove_all();
+        let mut checks = vec![];
+        for app in apps.iter().filter(|app| !app.exported) {
+            let check = gtk::CheckButton::new();
+            let row = adw::ActionRow::new();
+            row.set_title(&app.entry.name);
+            row.add_prefix(&check);
+            row.set_activatable_widget(Some(&check));
+            imp.apps_list.append(&row);
+            checks.push((app.desktop_file_path.clone(), check));
+        }
+        imp.app_checks.replace(checks);
+    }
+
+    fn export_selected(&self) {
+        let selected = self
+            .imp()
+            .app_checks
+            .borrow()
+            .iter()
+            .filter(|(_, check)| check.is_active())
+            .count();
+        self.advance(WizardEvent::AppsSelected(selected));
+    }
+}
@@ -2,15 +2,19 @@ use adw::prelude::*;
 use adw::subclass::prelude::*;
 use gtk::glib::{BoxedAnyObject, clone};
 use gtk::{gio, glib};
-use tracing::error;
+use tracing::{error, warn};
 
-use crate::backends::{ExportableApp, ExportableBinary};
+use crate::backends::{
+    AppGroup, ExportableApp, ExportableBinary, StaleExport, group_apps_by_category,
+    parse_env_assignments,
+};
 use crate::fakers::Command;
 use crate::gtk_utils::{TypedListStore, reaction};
 use crate::i18n::gettext;
 use crate::models::{Container, RootStore};
 
 use std::cell::{OnceCell, RefCell};
+use std::path::Path;
 
 use glib::VariantTy;
 use gtk::glib::{Properties, derived_properties};
@@ -37,7 +41,12 @@ mod imp {
         pub binaries_list_box: gtk::ListBox,
         pub binary_name_entry: adw::EntryRow,
         pub export_apps_group: adw::PreferencesGroup,
+        pub export_recommended_btn: gtk::Button,
+        // Desktop file paths of the recommended apps that aren't exported yet
+        pub recommended_apps: RefCell<Vec<String>>,
         pub export_binaries_group: adw::PreferencesGroup,
+        pub stale_exports_list_box: gtk::ListBox,
+        pub stale_exports_group: adw::PreferencesGroup,
     }
 
     #[derived_properties]
@@ -48,7 +57,12 @@ mod imp {
             obj.set_content_width(360);
             obj.set_content_height(640);
 
-            self.toolbar_view.add_top_bar(&adw::HeaderBar::new());
+            let header_bar = adw::HeaderBar::new();
+            let refresh_btn = gtk::Button::from_icon_name("view-refresh-symbolic");
+            refresh_btn.set_tooltip_text(Some(&gettext("Refresh")));
+            refresh_btn.set_action_name(Some("dialog.refresh"));
+            header_bar.pack_start(&refresh_btn);
+            self.toolbar_view.add_top_bar(&header_bar);
 
             self.content.set_orientation(gtk::Orientation::Vertical);
             self.content.set_spacing(6);
@@ -78,6 +92,15 @@ mod imp {
                 .set_title(&gettext("Exportable Apps"));
             self.export_apps_group
                 .set_description(Some(&gettext("No exportable apps found")));
+            self.export_recommended_btn
+                .set_label(&gettext("Export Recommended"));
+            self.export_recommended_btn.set_valign(gtk::Align::Center);
+            self.export_recommended_btn.add_css_class("flat");
+            self.export_recommended_btn
+                .set_action_name(Some("dialog.export-recommended-apps"));
+            self.export_recommended_btn.set_visible(false);
+            self.export_apps_group
+                .set_header_suffix(Some(&self.export_recommended_btn));
             self.export_apps_group.add(&self.list_box);
 
             self.apps_error_label
@@ -124,7 +147,31 @@ mod imp {
             self.binaries_error_label.set_margin_bottom(12);
             self.binaries_error_label.set_visible(false);
 
+            self.stale_exports_list_box.add_css_class("boxed-list");
+            self.stale_exports_list_box
+                .set_selection_mode(gtk::SelectionMode::None);
+
+            let cleanup_btn = gtk::Button::with_label(&gettext("Clean Up"));
+            cleanup_btn.set_valign(gtk::Align::Center);
+            cleanup_btn.add_css_class("destructive-action");
+            cleanup_btn.set_action_name(Some("dialog.cleanup-stale-exports"));
+
+            self.stale_exports_group.set_margin_start(12);
+            self.stale_exports_group.set_margin_end(12);
+            self.stale_exports_group.set_margin_top(12);
+            self.stale_exports_group.set_margin_bottom(0);
+            self.stale_exports_group
+                .set_title(&gettext("Stale Exports"));
+            self.stale_exports_group.set_description(Some(&gettext(
+                "These exported apps were removed from the container but are still on the host",
+            )));
+            self.stale_exports_group
+                .set_header_suffix(Some(&cleanup_btn));
+            self.stale_exports_group.add(&self.stale_exports_list_box);
+            self.stale_exports_group.set_visible(false);
+
             let content_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            content_box.append(&self.stale_exports_group);
             content_box.append(&self.export_apps_group);
             content_box.append(&self.apps_error_label);
             content_box.append(&self.export_binaries_group);
@@ -145,13 +192,44 @@ mod imp {
         type ParentType = adw::Dialog;
 
         fn class_init(klass: &mut Self::Class) {
+            klass.install_action("dialog.refresh", None, |this, _action, _target| {
+                this.container().reload_exports();
+            });
+            klass.install_action(
+                "dialog.cleanup-stale-exports",
+                None,
+                |this, _action, _target| {
+                    let container = this.container();
+                    let exports = container.stale_exports().data().unwrap_or_default();
+                    if !exports.is_empty() {
+                        this.root_store().remove_stale_exports(&container, exports);
+                    }
+                },
+            );
             klass.install_action(
                 "dialog.export-app",
                 Some(VariantTy::STRING),
                 |this, _action, target| {
                     let file_path = target.unwrap().str().unwrap();
-                    let container = this.container();
-                    this.root_store().export_app(&container, file_path);
+                    this.export_app_checking_host(file_path);
+                },
+            );
+            klass.install_action(
+                "dialog.export-recommended-apps",
+                None,
+                |this, _action, _target| {
+                    let apps = this.imp().recommended_apps.borrow().clone();
+                    if !apps.is_empty() {
+                        this.root_store().export_apps(&this.container(), apps);
+                    }
+                },
+            );
+            klass.install_action(
+                "dialog.launch-app-with-env",
+                Some(VariantTy::STRING),
+                |this, _action, target| {
+                    let file_path = target.unwrap().str().unwrap();
+                    this.launch_app_with_env(file_path);
                 },
             );
             klass.install_action(
@@ -280,18 +358,10 @@ impl ExportableAppsDialog {
                 this_clone.imp().export_apps_group.set_description(None);
             }
 
+            this_clone.update_recommended_apps(apps_data);
+
             this_clone.imp().stack.set_visible_child_name("apps");
-            let this = this_clone.clone();
-            this_clone
-                .imp()
-                .list_box
-                .bind_model(Some(apps_data.inner()), move |obj| {
-                    let app = obj
-                        .downcast_ref::<BoxedAnyObject>()
-                        .map(|obj| obj.borrow::<ExportableApp>())
-                        .unwrap();
-                    this.build_row(&app).upcast()
-                });
+            this_clone.render_app_groups(apps_data);
         };
 
         let this_clone = this.clone();
@@ -322,6 +392,29 @@ impl ExportableAppsDialog {
             );
         };
 
+        let this_clone = this.clone();
+        let render_stale_exports = move |stale_exports: &Vec<StaleExport>| {
+            let imp = this_clone.imp();
+            imp.stale_exports_list_box.remove_all();
+            for export in stale_exports {
+                let row = adw::ActionRow::new();
+                row.set_title(&export.file_name);
+                row.set_subtitle(&export.host_path.display().to_string());
+                imp.stale_exports_list_box.append(&row);
+            }
+            imp.stale_exports_group
+                .set_visible(!stale_exports.is_empty());
+        };
+
+        let stale_exports = this.container().stale_exports();
+        let render_stale_exports_closure = render_stale_exports.clone();
+        stale_exports.connect_success(move |stale_exports| {
+            render_stale_exports_closure(stale_exports);
+        });
+        stale_exports.connect_error(|error| {
+            error!("Error loading stale exports: {}", error);
+        });
+
         let apps = this.container().apps();
         let render_apps_closure = render_apps.clone();
         let this_for_apps_success = this.clone();
@@ -391,12 +484,142 @@ impl ExportableAppsDialog {
                 }
             });
 
-        container.apps().refetch();
-        container.binaries().refetch();
+        // Show the cached lists right away, they are refetched only when stale
+        if let Some(apps_data) = apps.data() {
+            render_apps(&apps_data);
+        }
+        if let Some(binaries_data) = binaries.data() {
+            render_binaries(&binaries_data);
+        }
+        if let Some(stale_exports_data) = stale_exports.data() {
+            render_stale_exports(&stale_exports_data);
+        }
+        this.imp()
+            .apps_error_label
+            .set_visible(apps.error().is_some());
+        this.imp()
+            .binaries_error_label
+            .set_visible(binaries.error().is_some());
+        if apps.error().is_some() || binaries.error().is_some() {
+            this.imp().stack.set_visible_child_name("apps");
+        }
+
+        container.load_exports();
 
         this
     }
 
+    fn update_recommended_apps(&self, apps_data: &TypedListStore<BoxedAnyObject>) {
+        let imp = self.imp();
+        let recommended: Vec<String> = match self.container().distro() {
+            Some(distro) => {
+                // Recommendations are matched by desktop file name, regardless of its directory
+                let unexported: Vec<(String, String)> = apps_data
+                    .iter()
+                    .filter_map(|obj| {
+                        let app = obj.borrow::<ExportableApp>();
+                        let file_name = Path::new(&app.desktop_file_path)
+                            .file_name()?
+                            .to_str()?
+                            .to_string();
+                        (!app.exported).then(|| (file_name, app.desktop_file_path.clone()))
+                    })
+                    .collect();
+                let names = distro.filter_recommended(unexported.iter().map(|(n, _)| n.as_str()));
+                unexported
+                    .iter()
+                    .filter(|(name, _)| names.contains(&name.as_str()))
+                    .map(|(_, path)| path.clone())
+                    .collect()
+            }
+            None => vec![],
+        };
+        imp.export_recommended_btn
+            .set_visible(!recommended.is_empty());
+        imp.export_recommended_btn.set_tooltip_text(Some(
+            &recommended
+                .iter()
+                .filter_map(|path| Path::new(path).file_stem()?.to_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+        imp.recommended_apps.replace(recommended);
+    }
+
+    /// Exports an app, first warning if the host has an app with the same name,
+    /// as both would appear in the app menu
+    fn export_app_checking_host(&self, desktop_file_path: &str) {
+        let this = self.clone();
+        let desktop_file_path = desktop_file_path.to_string();
+        glib::spawn_future_local(async move {
+            let container = this.container();
+            let root_store = this.root_store();
+            let file_name = Path::new(&desktop_file_path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            let clash = match root_store.distrobox().host_has_desktop(file_name).await {
+                Ok(clash) => clash,
+                Err(e) => {
+                    warn!(error = %e, "Failed to check host apps, exporting anyway");
+                    false
+                }
+            };
+            if !clash {
+                root_store.export_app(&container, &desktop_file_path);
+                return;
+            }
+
+            let app_name = container
+                .apps()
+                .data()
+                .and_then(|apps| {
+                    apps.iter().find_map(|obj| {
+                        let app = obj.borrow::<ExportableApp>();
+                        (app.desktop_file_path == desktop_file_path).then(|| app.entry.name.clone())
+                    })
+                })
+                .unwrap_or_else(|| file_name.trim_end_matches(".desktop").to_string());
+
+            let name_row = adw::EntryRow::new();
+            name_row.set_title(&gettext("Name in the App Menu"));
+            name_row.set_text(&format!("{} ({})", app_name, container.name()));
+            let list_box = gtk::ListBox::new();
+            list_box.add_css_class("boxed-list");
+            list_box.set_selection_mode(gtk::SelectionMode::None);
+            list_box.append(&name_row);
+
+            let dialog = adw::AlertDialog::new(
+                Some(&gettext("App Already Installed on Host")),
+                Some(&gettext(
+                    "The host already has an app with the same name, so both would appear in the app menu. You can rename the exported app to tell them apart.",
+                )),
+            );
+            dialog.set_extra_child(Some(&list_box));
+            dialog.add_response("cancel", &gettext("Cancel"));
+            dialog.add_response("export", &gettext("Export Anyway"));
+            dialog.add_response("rename", &gettext("Export Renamed"));
+            dialog.set_response_appearance("rename", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("rename"));
+            dialog.set_close_response("cancel");
+
+            dialog.connect_response(None, move |_dialog, response| {
+                let display_name = name_row.text();
+                match response {
+                    "export" => root_store.export_app(&container, &desktop_file_path),
+                    "rename" if !display_name.trim().is_empty() => root_store.export_app_as(
+                        &container,
+                        &desktop_file_path,
+                        display_name.trim(),
+                    ),
+                    "rename" => root_store.export_app(&container, &desktop_file_path),
+                    _ => {}
+                }
+            });
+            dialog.present(Some(&this));
+        });
+    }
+
     /// Helper method to perform the actual export of a binary
     fn do_export_binary(&self, binary_name: &str) {
         let container = self.container();
@@ -463,6 +686,60 @@ impl ExportableAppsDialog {
         });
     }
 
+    /// Shows the apps grouped by their primary category, each group with a button to export
+    /// all of its apps at once
+    fn render_app_groups(&self, apps_data: &TypedListStore<BoxedAnyObject>) {
+        let list_box = &self.imp().list_box;
+        list_box.remove_all();
+        let apps = apps_data
+            .iter()
+            .map(|obj| obj.borrow::<ExportableApp>().clone());
+        for group in group_apps_by_category(apps) {
+            list_box.append(&self.build_group_row(&group));
+        }
+    }
+
+    fn build_group_row(&self, group: &AppGroup) -> adw::ExpanderRow {
+        let row = adw::ExpanderRow::new();
+        row.set_title(&match group.category.as_deref() {
+            Some(category) => category_title(category),
+            None => gettext("Other"),
+        });
+        row.set_expanded(true);
+
+        let unexported = group.unexported_paths();
+        if !unexported.is_empty() {
+            let export_all_btn = gtk::Button::with_label(&gettext("Export All"));
+            export_all_btn.set_valign(gtk::Align::Center);
+            export_all_btn.add_css_class("flat");
+            // Previews the apps that get exported
+            export_all_btn.set_tooltip_text(Some(
+                &unexported
+                    .iter()
+                    .filter_map(|path| Path::new(path).file_stem()?.to_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+            let group = group.clone();
+            export_all_btn.connect_clicked(clone!(
+                #[weak(rename_to=this)]
+                self,
+                #[strong]
+                group,
+                move |_| {
+                    this.root_store()
+                        .export_app_group(&this.container(), &group);
+                }
+            ));
+            row.add_suffix(&export_all_btn);
+        }
+
+        for app in &group.apps {
+            row.add_row(&self.build_row(app));
+        }
+        row
+    }
+
     pub fn build_row(&self, app: &ExportableApp) -> adw::ActionRow {
         // Create the action row
         let row = adw::ActionRow::new();
@@ -506,6 +783,15 @@ impl ExportableAppsDialog {
             menu_model.append_item(&unexport_action);
         }
 
+        let launch_with_env_action = gio::MenuItem::new(
+            Some("Launch With Environment…"),
+            Some(&format!(
+                "dialog.launch-app-with-env(\"{}\")",
+                app.desktop_file_path
+            )),
+        );
+        menu_model.append_item(&launch_with_env_action);
+
         // Set up the popover menu
         let popover = gtk::PopoverMenu::from_model(Some(&menu_model));
         menu_button.set_popover(Some(&popover));
@@ -516,6 +802,65 @@ impl ExportableAppsDialog {
         row
     }
 
+    /// Asks for environment variables to set for a single launch of the app, useful for debugging
+    fn launch_app_with_env(&self, desktop_file_path: &str) {
+        let container = self.container();
+        let Some(app) = container.apps().data().and_then(|apps| {
+            apps.iter()
+                .map(|obj| obj.borrow::<ExportableApp>().clone())
+                .find(|app| app.desktop_file_path == desktop_file_path)
+        }) else {
+            return;
+        };
+
+        let env_row = adw::EntryRow::new();
+        env_row.set_title(&gettext("Environment Variables"));
+        env_row.set_text("GTK_DEBUG=interactive");
+        let list_box = gtk::ListBox::new();
+        list_box.add_css_class("boxed-list");
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.append(&env_row);
+
+        let dialog = adw::AlertDialog::new(
+            Some(&gettext("Launch With Environment")),
+            Some(&gettext(
+                "The variables, separated by spaces, are only set for this launch.",
+            )),
+        );
+        dialog.set_extra_child(Some(&list_box));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("launch", &gettext("Launch"));
+        dialog.set_response_appearance("launch", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("launch"));
+        dialog.set_close_response("cancel");
+
+        let is_valid = |text: &str| parse_env_assignments(text).is_ok();
+        env_row.connect_changed(clone!(
+            #[weak]
+            dialog,
+            move |row| {
+                let valid = is_valid(&row.text());
+                dialog.set_response_enabled("launch", valid);
+                if valid {
+                    row.remove_css_class("error");
+                } else {
+                    row.add_css_class("error");
+                }
+            }
+        ));
+
+        let root_store = self.root_store();
+        dialog.connect_response(None, move |_dialog, response| {
+            if response != "launch" {
+                return;
+            }
+            if let Ok(env) = parse_env_assignments(&env_row.text()) {
+                root_store.launch_app_with_env(&container, app.clone(), env);
+            }
+        });
+        dialog.present(Some(self));
+    }
+
     pub fn build_binary_row(&self, binary: &ExportableBinary) -> adw::ActionRow {
         // Create the action row
         let row = adw::ActionRow::new();
@@ -549,3 +894,21 @@ impl ExportableAppsDialog {
         row
     }
 }
+
+/// Readable name of a main category of the Desktop Menu spec
+fn category_title(category: &str) -> String {
+    match category {
+        "AudioVideo" => gettext("Multimedia"),
+        "Development" => gettext("Development"),
+        "Education" => gettext("Education"),
+        "Game" => gettext("Games"),
+        "Graphics" => gettext("Graphics"),
+        "Network" => gettext("Internet"),
+        "Office" => gettext("Office"),
+        "Science" => gettext("Science"),
+        "Settings" => gettext("Settings"),
+        "System" => gettext("System"),
+        "Utility" => gettext("Utilities"),
+        other => other.to_string(),
+    }
+}
@@ -1,12 +1,16 @@
+pub mod adopt_container_dialog;
 pub mod command_log_dialog;
 pub mod create_distrobox_dialog;
 pub mod create_distrobox_helpers;
+pub mod create_wizard_dialog;
 pub mod exportable_apps_dialog;
 pub mod preferences_dialog;
 pub mod task_manager_dialog;
 
+pub use adopt_container_dialog::AdoptContainerDialog;
 pub use command_log_dialog::CommandLogDialog;
 pub use create_distrobox_dialog::CreateDistroboxDialog;
+pub use create_wizard_dialog::CreateWizardDialog;
 pub use exportable_apps_dialog::ExportableAppsDialog;
 pub use preferences_dialog::PreferencesDialog;
 pub use task_manager_dialog::TaskManagerDialog;
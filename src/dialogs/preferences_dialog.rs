@@ -1,4 +1,5 @@
 use crate::backends::supported_terminals;
+use crate::gettext_f;
 use crate::i18n::gettext;
 use crate::models::{DialogType, RootStore};
 use crate::widgets::TerminalComboRow;
@@ -155,6 +156,62 @@ mod imp {
 
             distrobox_group.add(&no_entry_row);
 
+            let custom_home_row = adw::SwitchRow::new();
+            custom_home_row.set_title(&gettext("Default to custom home"));
+            custom_home_row.set_subtitle(&gettext(
+                "Give each new container its own home directory, instead of sharing yours.",
+            ));
+            settings
+                .bind("default-custom-home", &custom_home_row, "active")
+                .build();
+            distrobox_group.add(&custom_home_row);
+
+            let custom_home_base_row = adw::EntryRow::new();
+            custom_home_base_row.set_title(&gettext("Custom homes directory"));
+            settings
+                .bind("custom-home-base", &custom_home_base_row, "text")
+                .build();
+            custom_home_row
+                .bind_property("active", &custom_home_base_row, "sensitive")
+                .sync_create()
+                .build();
+            distrobox_group.add(&custom_home_base_row);
+
+            let command_log_row = adw::SwitchRow::new();
+            command_log_row.set_title(&gettext("Record executed commands"));
+            command_log_row.set_subtitle(&gettext(
+                "Keeps every command in the command log. Disable to reduce memory usage.",
+            ));
+            settings
+                .bind("command-log-capture", &command_log_row, "active")
+                .build();
+
+            distrobox_group.add(&command_log_row);
+
+            let dedicated_session_row = adw::SwitchRow::new();
+            dedicated_session_row.set_title(&gettext("Launch apps in a dedicated session"));
+            dedicated_session_row.set_subtitle(&gettext(
+                "Each launch, and each app exported afterwards, starts a new instance with a session bus of its own inside the container. Apps don't run in a separate container: distrobox can't clone a running one.",
+            ));
+            settings
+                .bind(
+                    "launch-apps-dedicated-session",
+                    &dedicated_session_row,
+                    "active",
+                )
+                .build();
+            distrobox_group.add(&dedicated_session_row);
+
+            let offline_row = adw::SwitchRow::new();
+            offline_row.set_title(&gettext("Offline mode"));
+            offline_row.set_subtitle(&gettext(
+                "Never download anything. Containers can only be created from images available locally, and can't be assembled or upgraded.",
+            ));
+            settings
+                .bind("offline-mode", &offline_row, "active")
+                .build();
+            distrobox_group.add(&offline_row);
+
             let distrobox_source_row = adw::ComboRow::new();
             distrobox_source_row.set_title(&gettext("Distrobox Source"));
             let model =
@@ -178,9 +235,69 @@ mod imp {
                     let _ = settings.set_string("distrobox-executable", "host");
                 }
             });
+            // The choice gets reverted to the host when the bundled version can't be used
+            obj.root_store().settings().connect_changed(
+                Some("distrobox-executable"),
+                clone!(
+                    #[weak]
+                    distrobox_source_row,
+                    move |settings, _key| {
+                        let selected =
+                            u32::from(settings.string("distrobox-executable") == "bundled");
+                        if distrobox_source_row.selected() != selected {
+                            distrobox_source_row.set_selected(selected);
+                        }
+                    }
+                ),
+            );
 
             distrobox_group.add(&distrobox_source_row);
 
+            let pinned_version_row = adw::EntryRow::new();
+            pinned_version_row.set_title(&gettext("Pinned Bundled Version"));
+            pinned_version_row.set_tooltip_text(Some(&gettext_f!(
+                "Leave empty to download the default version, {version}",
+                "version" => crate::distrobox_downloader::DISTROBOX_VERSION
+            )));
+            pinned_version_row.set_show_apply_button(true);
+            pinned_version_row.set_text(&settings.string("distrobox-pinned-version"));
+            pinned_version_row.connect_changed(|row| row.remove_css_class("error"));
+            let settings_for_pinned_version = settings.clone();
+            pinned_version_row.connect_apply(move |row| {
+                let version = row.text();
+                let version = version.trim();
+                if !version.is_empty()
+                    && crate::distrobox_downloader::validate_version(version).is_err()
+                {
+                    row.add_css_class("error");
+                    return;
+                }
+                let _ = settings_for_pinned_version.set_string("distrobox-pinned-version", version);
+            });
+            distrobox_group.add(&pinned_version_row);
+
+            let pinned_sha256_row = adw::EntryRow::new();
+            pinned_sha256_row.set_title(&gettext("Pinned Version Checksum"));
+            pinned_sha256_row.set_tooltip_text(Some(&gettext(
+                "SHA256 of the release tarball of the pinned version, needed to verify its download",
+            )));
+            pinned_sha256_row.set_show_apply_button(true);
+            pinned_sha256_row.set_text(&settings.string("distrobox-pinned-sha256"));
+            pinned_sha256_row.connect_changed(|row| row.remove_css_class("error"));
+            let settings_for_pinned_sha256 = settings.clone();
+            pinned_sha256_row.connect_apply(move |row| {
+                let sha256 = row.text();
+                let sha256 = sha256.trim();
+                if !sha256.is_empty()
+                    && crate::distrobox_downloader::validate_checksum(sha256).is_err()
+                {
+                    row.add_css_class("error");
+                    return;
+                }
+                let _ = settings_for_pinned_sha256.set_string("distrobox-pinned-sha256", sha256);
+            });
+            distrobox_group.add(&pinned_sha256_row);
+
             // Add version row
             let version_row = adw::ActionRow::new();
             version_row.set_title(&gettext("Distrobox Version"));
@@ -235,6 +352,40 @@ mod imp {
             distrobox_group.add(&redownload_btn);
 
             page.add(&distrobox_group);
+
+            let timeouts_group = adw::PreferencesGroup::new();
+            timeouts_group.set_title(&gettext("Timeouts"));
+            timeouts_group.set_description(Some(&gettext(
+                "Seconds to wait before giving up on each kind of operation. 0 waits forever.",
+            )));
+            for (key, title) in [
+                ("version-timeout", gettext("Distrobox version check")),
+                ("list-timeout", gettext("Listing containers")),
+                ("images-timeout", gettext("Listing images")),
+            ] {
+                let row = adw::SpinRow::with_range(0.0, 3600.0, 1.0);
+                row.set_title(&title);
+                row.set_value(settings.uint(key).into());
+                let settings = settings.clone();
+                row.connect_value_notify(move |row| {
+                    let _ = settings.set_uint(key, row.value() as u32);
+                });
+                timeouts_group.add(&row);
+            }
+            let poll_interval_row = adw::SpinRow::with_range(1.0, 3600.0, 1.0);
+            poll_interval_row.set_title(&gettext("Containers polling interval"));
+            poll_interval_row.set_subtitle(&gettext(
+                "Seconds between refreshes of the containers list, when podman events are unavailable.",
+            ));
+            poll_interval_row.set_value(settings.uint("containers-poll-interval").into());
+            let settings_for_poll_interval = settings.clone();
+            poll_interval_row.connect_value_notify(move |row| {
+                let _ = settings_for_poll_interval
+                    .set_uint("containers-poll-interval", row.value() as u32);
+            });
+            timeouts_group.add(&poll_interval_row);
+            page.add(&timeouts_group);
+
             obj.add(&page);
         }
     }
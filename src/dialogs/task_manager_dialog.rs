@@ -270,6 +270,49 @@ impl TaskManagerDialog {
             update_status_ui(task);
         });
 
+        // Commands executed by the task, taken from the command log
+        let commands_box = gtk::Box::new(gtk::Orientation::Vertical, 3);
+        let commands_expander = gtk::Expander::new(Some(&gettext("Commands")));
+        commands_expander.set_child(Some(&commands_box));
+        let update_commands = clone!(
+            #[weak(rename_to=this)]
+            self,
+            #[weak]
+            commands_box,
+            move |task: &DistroboxTask| {
+                while let Some(child) = commands_box.first_child() {
+                    commands_box.remove(&child);
+                }
+                let events = this.root_store().command_runner().output_events();
+                let commands = task.commands(&events);
+                if commands.is_empty() {
+                    let label = gtk::Label::new(Some(&gettext("No commands recorded")));
+                    label.set_xalign(0.0);
+                    label.add_css_class("dim-label");
+                    commands_box.append(&label);
+                }
+                for command in commands {
+                    let label = gtk::Label::new(Some(&command.to_string()));
+                    label.set_xalign(0.0);
+                    label.set_wrap(true);
+                    label.set_selectable(true);
+                    label.add_css_class("monospace");
+                    label.add_css_class("caption");
+                    commands_box.append(&label);
+                }
+            }
+        );
+        update_commands(task);
+        commands_expander.connect_expanded_notify(clone!(
+            #[weak]
+            task,
+            #[strong]
+            update_commands,
+            move |_| update_commands(&task)
+        ));
+        task.connect_status_notify(move |task| update_commands(task));
+        content.append(&commands_expander);
+
         // Get VTE terminal from task
         let vte_terminal = task.vte_terminal();
 
@@ -4,18 +4,19 @@ use crate::models::DistroboxTask;
 use crate::models::RootStore;
 use anyhow::{Context, anyhow};
 use gtk::glib;
-use std::path::PathBuf;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 
 pub const DISTROBOX_VERSION: &str = "1.8.2.4";
 // SHA256 of the tar.gz file from github
 pub const DISTROBOX_SHA256: &str =
     "83eab6ba893dce56f0523a70ebfcf2a78f7785637a6895255777ef1b72d4eb89";
 
-pub fn get_bundled_distrobox_path() -> PathBuf {
+pub fn get_bundled_distrobox_path(version: &str) -> PathBuf {
     let user_data_dir = glib::user_data_dir();
     user_data_dir
         .join("distroshelf")
-        .join(format!("distrobox-{}", DISTROBOX_VERSION))
+        .join(format!("distrobox-{}", version))
         .join("distrobox")
 }
 
@@ -24,21 +25,70 @@ pub fn get_bundled_distrobox_dir() -> PathBuf {
     user_data_dir.join("distroshelf")
 }
 
+/// Checks that a version to pin looks like a distrobox release tag, e.g. "1.8.2.4".
+/// Whether the release exists is only known when downloading it.
+pub fn validate_version(version: &str) -> anyhow::Result<()> {
+    let parts: Vec<&str> = version.split('.').collect();
+    let valid = (3..=4).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if !valid {
+        return Err(anyhow!(
+            "\"{version}\" isn't a distrobox version, expected something like {DISTROBOX_VERSION}"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a checksum is a SHA256 in hex, like the ones printed by `sha256sum`
+pub fn validate_checksum(sha256: &str) -> anyhow::Result<()> {
+    if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("\"{sha256}\" isn't a SHA256 checksum"));
+    }
+    Ok(())
+}
+
+/// The SHA256 the release tarball of `version` must have: the known one for DISTROBOX_VERSION,
+/// otherwise the one given by the user along with the pinned version.
+/// Distrobox doesn't publish checksums of its releases, so a pinned version without one
+/// can't be verified and isn't installed.
+pub fn expected_checksum(version: &str, pinned_sha256: &str) -> anyhow::Result<String> {
+    if version == DISTROBOX_VERSION {
+        return Ok(DISTROBOX_SHA256.to_string());
+    }
+    let sha256 = pinned_sha256.trim();
+    if sha256.is_empty() {
+        return Err(anyhow!(
+            "No checksum given for distrobox {version}, add the SHA256 of its release tarball in the preferences"
+        ));
+    }
+    validate_checksum(sha256)?;
+    Ok(sha256.to_lowercase())
+}
+
 /// Resolves the best available bundled distrobox path.
-/// Returns the current version's path if it exists, otherwise finds the most recent older version.
+/// Returns the path of `version` if it exists, otherwise finds the most recent other version.
 /// Returns None if no bundled version is found at all.
-pub fn resolve_bundled_distrobox_path() -> Option<PathBuf> {
-    let current = get_bundled_distrobox_path();
+pub fn resolve_bundled_distrobox_path(version: &str) -> Option<PathBuf> {
+    let current = get_bundled_distrobox_path(version);
     if current.exists() {
         return Some(current);
     }
     find_latest_bundled_version()
 }
 
-/// Returns true if the user has a bundled distrobox but it's not the latest (DISTROBOX_VERSION).
-/// This means an update is available to download.
-pub fn is_bundled_update_available() -> bool {
-    let current = get_bundled_distrobox_path();
+/// Whether the path is a regular file with an executable bit set
+pub fn is_executable(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Returns true if the user has a bundled distrobox but it's not `version`, the default one
+/// (DISTROBOX_VERSION) or the pinned one. This means an update is available to download.
+pub fn is_bundled_update_available(version: &str) -> bool {
+    let current = get_bundled_distrobox_path(version);
     if current.exists() {
         return false; // Already have the latest
     }
@@ -71,10 +121,10 @@ fn find_latest_bundled_version() -> Option<PathBuf> {
     versions.last().map(|(_, path)| path.clone())
 }
 
-/// Removes all bundled distrobox version directories except the current one (DISTROBOX_VERSION).
-pub fn cleanup_old_bundled_versions() {
+/// Removes all bundled distrobox version directories except the one of `version`.
+pub fn cleanup_old_bundled_versions(version: &str) {
     let parent = get_bundled_distrobox_dir();
-    let current_dir_name = format!("distrobox-{}", DISTROBOX_VERSION);
+    let current_dir_name = format!("distrobox-{}", version);
 
     let entries = match std::fs::read_dir(&parent) {
         Ok(e) => e,
@@ -109,19 +159,55 @@ fn log(task: &DistroboxTask, msg: &str) {
     task.append_output("\n");
 }
 
+/// Extracts the last percentage printed by `curl --progress-bar`, as a fraction
+fn parse_curl_progress(output: &str) -> Option<f64> {
+    output
+        .split(|c: char| c.is_whitespace() || c == '#')
+        .rev()
+        .find_map(|token| token.strip_suffix('%')?.parse::<f64>().ok())
+        .map(|percent| (percent / 100.0).clamp(0.0, 1.0))
+}
+
+fn release_url(version: &str) -> String {
+    format!(
+        "https://github.com/89luca89/distrobox/archive/refs/tags/{}.tar.gz",
+        version
+    )
+}
+
+/// Asks GitHub for the release tarball without downloading it, failing when it doesn't exist
+fn release_check_command(version: &str) -> Command {
+    let mut cmd = Command::new("curl");
+    cmd.args(["--head", "--silent", "--fail", "--location"]);
+    cmd.arg(release_url(version));
+    cmd
+}
+
+/// Downloads `version` of distrobox. Versions other than DISTROBOX_VERSION are checked against
+/// the published releases first, and verified with `pinned_sha256`.
 pub async fn download_distrobox(
+    command_runner: CommandRunner,
     task: DistroboxTask,
+    version: &str,
+    pinned_sha256: &str,
     root_store_weak: glib::WeakRef<RootStore>,
 ) -> anyhow::Result<()> {
-    // We should be able to actually use a CommandRunner runs in the flatpak sandbox, because it has all the tools we need.
-    // Also, the data folder is writable there and should be mapped to the host.
-    let command_runner = CommandRunner::new_real();
+    validate_version(version)?;
+    let expected_sha256 = expected_checksum(version, pinned_sha256)?;
     let download_dir = get_bundled_distrobox_dir();
     let tarball_path = download_dir.join("distrobox.tar.gz");
-    let url = format!(
-        "https://github.com/89luca89/distrobox/archive/refs/tags/{}.tar.gz",
-        DISTROBOX_VERSION
-    );
+    let url = release_url(version);
+
+    if version != DISTROBOX_VERSION {
+        log(&task, &format!("Looking for the {} release...", version));
+        let output = command_runner
+            .output(release_check_command(version))
+            .await
+            .with_context(|| format!("Failed to look for the distrobox {version} release"))?;
+        if !output.status.success() {
+            return Err(anyhow!("Distrobox {version} isn't an available release"));
+        }
+    }
 
     // Ensure directory exists
     std::fs::create_dir_all(&download_dir).context("Failed to create download directory")?;
@@ -135,6 +221,7 @@ pub async fn download_distrobox(
     log(&task, &format!("Downloading {}...", url));
     let mut curl_cmd = Command::new("curl");
     curl_cmd.arg("-L");
+    curl_cmd.arg("--progress-bar");
     curl_cmd.arg("-o");
     curl_cmd.arg(&tarball_path);
     curl_cmd.arg(&url);
@@ -145,7 +232,13 @@ pub async fn download_distrobox(
         .spawn(curl_cmd)
         .context("Failed to run curl")?;
 
-    task.handle_child_output(child).await?;
+    task.handle_child_output_with(child, |chunk| {
+        if let Some(progress) = parse_curl_progress(&String::from_utf8_lossy(chunk)) {
+            task.set_progress(progress);
+        }
+    })
+    .await?;
+    task.set_progress(1.0);
 
     // 2. Verify SHA256
     log(&task, "Verifying checksum...");
@@ -162,10 +255,11 @@ pub async fn download_distrobox(
     let stdout = String::from_utf8_lossy(&output.stdout);
     let calculated_hash = stdout.split_whitespace().next().unwrap_or_default();
 
-    if calculated_hash != DISTROBOX_SHA256 {
+    if calculated_hash != expected_sha256 {
+        std::fs::remove_file(&tarball_path).context("Failed to remove tarball")?;
         return Err(anyhow!(
             "Checksum mismatch. Expected {}, got {}",
-            DISTROBOX_SHA256,
+            expected_sha256,
             calculated_hash
         ));
     }
@@ -190,7 +284,7 @@ pub async fn download_distrobox(
     std::fs::remove_file(&tarball_path).context("Failed to remove tarball")?;
 
     // 4. Make executable (it should be already, but just in case)
-    let binary_path = get_bundled_distrobox_path();
+    let binary_path = get_bundled_distrobox_path(version);
     log(
         &task,
         &format!("Setting executable permissions on {:?}...", binary_path),
@@ -211,7 +305,7 @@ pub async fn download_distrobox(
 
     // Clean up old bundled versions
     log(&task, "Cleaning up old bundled versions...");
-    cleanup_old_bundled_versions();
+    cleanup_old_bundled_versions(version);
 
     if let Some(root_store) = root_store_weak.upgrade() {
         root_store.distrobox_version().refetch();
@@ -221,3 +315,102 @@ pub async fn download_distrobox(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::NullCommandRunnerBuilder;
+    use std::io;
+
+    #[test]
+    fn parses_curl_progress_bar() {
+        assert_eq!(parse_curl_progress("\r##########      12.5%"), Some(0.125));
+        assert_eq!(
+            parse_curl_progress("\r###  5.0%\r#######  50.0%"),
+            Some(0.5)
+        );
+        assert_eq!(parse_curl_progress("\r#=#=#"), None);
+    }
+
+    const PINNED_SHA256: &str = "5d1c1c3a7f1a27c2a1dd8a7b0f1c1e53ad1bc2b1fd1e4c5b0f3c1c7d9e2a4b6c";
+
+    #[test]
+    fn validates_versions() {
+        assert!(validate_version("1.8.2.4").is_ok());
+        assert!(validate_version("1.7.0").is_ok());
+        for invalid in ["", "latest", "1.8", "1..2", "v1.8.2.4", "1.8.2.4; rm -rf ~"] {
+            assert!(
+                validate_version(invalid).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[gtk::test]
+    fn download_uses_the_pinned_version() {
+        let runner = NullCommandRunnerBuilder::new()
+            .cmd_full(release_check_command("1.7.2.1"), || {
+                Err(io::Error::other("not found"))
+            })
+            .build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let task = DistroboxTask::new("system", "download", |_| async { Ok(()) });
+
+        let result = smol::block_on(download_distrobox(
+            runner.clone(),
+            task.clone(),
+            "1.7.2.1",
+            PINNED_SHA256,
+            glib::WeakRef::new(),
+        ));
+        assert!(result.is_err());
+        let commands: Vec<String> = tracker
+            .items()
+            .iter()
+            .filter_map(|event| Some(event.command()?.to_string()))
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                "curl --head --silent --fail --location https://github.com/89luca89/distrobox/archive/refs/tags/1.7.2.1.tar.gz"
+            ]
+        );
+
+        // Invalid versions are rejected before running anything
+        let result = smol::block_on(download_distrobox(
+            runner.clone(),
+            task.clone(),
+            "latest",
+            PINNED_SHA256,
+            glib::WeakRef::new(),
+        ));
+        assert!(result.is_err());
+        assert_eq!(tracker.items().len(), 2);
+
+        // So are pinned versions that can't be verified
+        let result = smol::block_on(download_distrobox(
+            runner,
+            task,
+            "1.7.2.1",
+            "",
+            glib::WeakRef::new(),
+        ));
+        assert!(result.is_err());
+        assert_eq!(tracker.items().len(), 2);
+    }
+
+    #[test]
+    fn expects_a_checksum_for_pinned_versions() {
+        assert_eq!(
+            expected_checksum(DISTROBOX_VERSION, "").unwrap(),
+            DISTROBOX_SHA256
+        );
+        assert_eq!(
+            expected_checksum("1.7.2.1", &PINNED_SHA256.to_uppercase()).unwrap(),
+            PINNED_SHA256
+        );
+        assert!(expected_checksum("1.7.2.1", "").is_err());
+        assert!(expected_checksum("1.7.2.1", "abc123").is_err());
+    }
+}
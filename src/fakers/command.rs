@@ -109,6 +109,26 @@ impl Command {
         v.extend(self.args.iter().cloned());
         v
     }
+
+    /// Formats the command so that it can be pasted into a POSIX shell
+    pub fn to_shell_string(&self) -> String {
+        self.to_vec()
+            .iter()
+            .map(|arg| shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Quotes an argument for a POSIX shell, leaving it untouched when it's safe
+pub fn shell_quote(arg: &OsStr) -> String {
+    let arg = arg.to_string_lossy();
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg.into_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
 }
 
 impl Display for Command {
@@ -187,6 +207,18 @@ mod tests {
         assert_eq!(cmd1.args[3], "hello");
     }
 
+    #[test]
+    fn test_command_to_shell_string() {
+        let cmd = Command::new_with_args(
+            "distrobox",
+            ["enter", "my box", "--", "echo", "it's", "", "--name=ok"],
+        );
+        assert_eq!(
+            cmd.to_shell_string(),
+            r"distrobox enter 'my box' -- echo 'it'\''s' '' --name=ok"
+        );
+    }
+
     #[test]
     fn test_command_remove_flag_arg() {
         let mut cmd = Command::new("ls");
@@ -10,6 +10,7 @@ use std::{
     process::ExitStatus,
     rc::Rc,
     sync::Arc,
+    time::SystemTime,
 };
 
 use crate::fakers::{Command, FdMode, OutputTracker};
@@ -22,6 +23,7 @@ use futures::{
 
 type ResponseFn = Rc<dyn Fn() -> Result<String, io::Error>>;
 type ResponseMap = HashMap<Vec<String>, ResponseFn>;
+type ExitStatusMap = HashMap<Vec<String>, ExitStatus>;
 
 #[derive(Debug, Clone)]
 pub enum CommandRunnerEvent {
@@ -79,11 +81,40 @@ impl CommandRunner {
         }
     }
 
+    /// Returns the tracker of the command events. It records them only while the capture
+    /// is enabled, see `set_output_capture`.
     pub fn output_tracker(&self) -> OutputTracker<CommandRunnerEvent> {
-        self.output_tracker.enable();
         self.output_tracker.clone()
     }
 
+    /// Turns the capture of command events on or off. When off, no event is recorded.
+    pub fn set_output_capture(&self, enabled: bool) {
+        if enabled {
+            self.output_tracker.enable();
+        } else {
+            self.output_tracker.disable();
+        }
+    }
+
+    pub fn is_output_capture_enabled(&self) -> bool {
+        self.output_tracker.is_enabled()
+    }
+
+    /// Number of captured command events, used to mark the start and end of a task
+    pub fn output_events_count(&self) -> usize {
+        self.output_tracker.len()
+    }
+
+    /// Returns the captured command events, without enabling the capture
+    pub fn output_events(&self) -> Vec<CommandRunnerEvent> {
+        self.output_tracker.items()
+    }
+
+    /// Returns the captured command events with the time they happened
+    pub fn timed_output_events(&self) -> Vec<(SystemTime, CommandRunnerEvent)> {
+        self.output_tracker.timed_items()
+    }
+
     fn event_id(&self) -> usize {
         self.output_tracker.len()
     }
@@ -92,6 +123,15 @@ impl CommandRunner {
         self.inner.wrap_command(command)
     }
 
+    /// The prefix added to every command before running it, e.g. "flatpak-spawn --host" inside
+    /// the Flatpak sandbox. None when commands run as they are logged.
+    pub fn command_prefix(&self) -> Option<String> {
+        let probe = Command::new("true");
+        let wrapped = self.wrap_command(probe.clone()).to_string();
+        let prefix = wrapped.strip_suffix(&probe.to_string())?.trim_end();
+        (!prefix.is_empty()).then(|| prefix.to_string())
+    }
+
     pub fn spawn(&self, command: Command) -> io::Result<Box<dyn Child + Send>> {
         self.output_tracker.push(CommandRunnerEvent::Spawned(
             self.event_id(),
@@ -182,6 +222,7 @@ impl InnerCommandRunner for RealCommandRunner {
 #[derive(Default, Clone)]
 pub struct NullCommandRunnerBuilder {
     responses: ResponseMap,
+    exit_statuses: ExitStatusMap,
     #[allow(dead_code)]
     fallback_exit_status: ExitStatus,
 }
@@ -207,6 +248,15 @@ impl NullCommandRunnerBuilder {
         self.responses.insert(key, Rc::new(out));
         self
     }
+    /// Makes `cmd` exit with `code`, instead of succeeding
+    #[allow(dead_code)]
+    pub fn cmd_exit_code(&mut self, cmd: Command, code: i32) -> &mut Self {
+        let key = NullCommandRunner::key_for_cmd(&cmd);
+        // A raw wait status holds the exit code in its second byte
+        self.exit_statuses
+            .insert(key, ExitStatus::from_raw(code << 8));
+        self
+    }
     #[allow(dead_code)]
     pub fn fallback(&mut self, status: ExitStatus) -> &mut Self {
         self.fallback_exit_status = status;
@@ -215,6 +265,7 @@ impl NullCommandRunnerBuilder {
     pub fn build(&self) -> CommandRunner {
         let inner = Rc::new(NullCommandRunner {
             responses: self.responses.clone(),
+            exit_statuses: self.exit_statuses.clone(),
             fallback_exit_status: self.fallback_exit_status,
         });
         CommandRunner::new(inner)
@@ -224,6 +275,7 @@ impl NullCommandRunnerBuilder {
 #[derive(Default, Clone)]
 pub struct NullCommandRunner {
     responses: ResponseMap,
+    exit_statuses: ExitStatusMap,
     #[allow(dead_code)]
     fallback_exit_status: ExitStatus,
 }
@@ -238,6 +290,12 @@ impl NullCommandRunner {
         key.insert(0, command.program.to_string_lossy().to_string());
         key
     }
+    fn exit_status_for_key(&self, key: &[String]) -> ExitStatus {
+        self.exit_statuses
+            .get(key)
+            .copied()
+            .unwrap_or(ExitStatus::from_raw(0))
+    }
 }
 
 impl InnerCommandRunner for NullCommandRunner {
@@ -248,9 +306,13 @@ impl InnerCommandRunner for NullCommandRunner {
             .get(&key[..])
             .cloned()
             .unwrap_or(Rc::new(|| Ok(String::new())));
-        let stub = StubChild::new_null(vec![], Cursor::new(response()?), Cursor::new(""), || {
-            Ok(ExitStatus::from_raw(0))
-        });
+        let status = self.exit_status_for_key(&key);
+        let stub = StubChild::new_null(
+            vec![],
+            Cursor::new(response()?),
+            Cursor::new(""),
+            move || Ok(status),
+        );
         Ok(Box::new(stub))
     }
     fn output(
@@ -263,10 +325,11 @@ impl InnerCommandRunner for NullCommandRunner {
             .get(&key[..])
             .cloned()
             .unwrap_or(Rc::new(|| Ok(String::new())));
+        let status = self.exit_status_for_key(&key);
 
         async move {
             Ok(Output {
-                status: ExitStatus::from_raw(0),
+                status,
                 stdout: response()?.into(),
                 stderr: vec![],
             })
@@ -425,6 +488,7 @@ mod tests {
     fn test_output_tracker() {
         let runner = CommandRunner::new_null();
         let tracker = runner.output_tracker();
+        tracker.enable();
 
         assert_eq!(tracker.len(), 0);
 
@@ -442,6 +506,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_output_capture_disabled() {
+        let runner = CommandRunner::new_null();
+        runner.set_output_capture(false);
+
+        let _ = block_on(runner.output(Command::new_with_args("ls", ["-la"])));
+        let _ = runner.spawn(Command::new_with_args("sleep", ["1"]));
+
+        assert!(!runner.is_output_capture_enabled());
+        assert!(runner.output_events().is_empty());
+        assert!(runner.output_tracker.items().is_empty());
+
+        // Getting the tracker doesn't turn the capture back on
+        let tracker = runner.output_tracker();
+        let _ = block_on(runner.output(Command::new_with_args("ls", ["-la"])));
+        assert!(!runner.is_output_capture_enabled());
+        assert!(tracker.items().is_empty());
+
+        // Turning it back on at runtime only captures the following commands
+        runner.set_output_capture(true);
+        let _ = block_on(runner.output(Command::new_with_args("ls", ["-la"])));
+        assert_eq!(runner.output_events().len(), 2);
+    }
+
+    #[test]
+    fn test_command_prefix() {
+        let runner = CommandRunner::new_null();
+        assert_eq!(runner.command_prefix(), None);
+
+        let flatpak_runner = runner.map_cmd(crate::backends::flatpak::map_flatpak_spawn_host);
+        assert_eq!(
+            flatpak_runner.command_prefix().as_deref(),
+            Some("flatpak-spawn --host")
+        );
+        let cmd = Command::new_with_args("distrobox", ["list", "--no-color"]);
+        assert_eq!(
+            flatpak_runner.wrap_command(cmd).to_string(),
+            "flatpak-spawn --host distrobox list --no-color"
+        );
+    }
+
     #[test]
     fn test_map_cmd() {
         let runner = NullCommandRunnerBuilder::new()
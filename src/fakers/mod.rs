@@ -3,7 +3,7 @@ mod command_runner;
 mod host_env;
 mod output_tracker;
 
-pub use command::{Command, FdMode};
+pub use command::{Command, FdMode, shell_quote};
 pub use command_runner::{Child, CommandRunner, CommandRunnerEvent, NullCommandRunnerBuilder};
 pub use host_env::resolve_host_env;
 pub use output_tracker::OutputTracker;
@@ -1,8 +1,9 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, time::SystemTime};
 
 #[derive(Default, Clone, Debug)]
 pub struct OutputTracker<T> {
-    store: Rc<RefCell<Option<Vec<T>>>>,
+    // Each item is stored with the time it was captured
+    store: Rc<RefCell<Option<Vec<(SystemTime, T)>>>>,
 }
 
 impl<T> OutputTracker<T> {
@@ -22,6 +23,15 @@ impl<T> OutputTracker<T> {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    pub fn is_enabled(&self) -> bool {
+        self.store.borrow().is_some()
+    }
+
+    /// Stops capturing items, dropping the ones captured so far
+    pub fn disable(&self) {
+        *self.store.borrow_mut() = None;
+    }
 }
 
 impl<T: Clone + std::fmt::Debug> OutputTracker<T> {
@@ -33,10 +43,18 @@ impl<T: Clone + std::fmt::Debug> OutputTracker<T> {
     }
     pub fn push(&self, item: T) {
         if let Some(v) = &mut *self.store.borrow_mut() {
-            v.push(item);
+            v.push((SystemTime::now(), item));
         }
     }
     pub fn items(&self) -> Vec<T> {
+        if let Some(v) = &*self.store.borrow() {
+            v.iter().map(|(_, item)| item.clone()).collect()
+        } else {
+            vec![]
+        }
+    }
+    /// Like `items`, along with the time each item was captured
+    pub fn timed_items(&self) -> Vec<(SystemTime, T)> {
         if let Some(v) = &*self.store.borrow() {
             v.clone()
         } else {
@@ -123,6 +141,36 @@ mod tests {
         assert_eq!(tracker.items(), vec!["item1", "item2"]);
     }
 
+    #[test]
+    fn test_output_tracker_disable() {
+        let tracker: OutputTracker<String> = OutputTracker::new();
+        tracker.enable();
+        tracker.push("item1".to_string());
+        assert!(tracker.is_enabled());
+
+        tracker.disable();
+        tracker.push("item2".to_string());
+
+        assert!(!tracker.is_enabled());
+        assert!(tracker.items().is_empty());
+    }
+
+    #[test]
+    fn test_output_tracker_timed_items() {
+        let tracker: OutputTracker<i32> = OutputTracker::new();
+        tracker.enable();
+
+        let before = SystemTime::now();
+        tracker.push(1);
+        tracker.push(2);
+
+        let items = tracker.timed_items();
+        assert_eq!(items.len(), 2);
+        assert!(items[0].0 >= before);
+        assert!(items[1].0 >= items[0].0);
+        assert_eq!(items[1].1, 2);
+    }
+
     #[test]
     fn test_output_tracker_with_custom_type() {
         #[derive(Clone, Debug, PartialEq)]
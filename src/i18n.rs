@@ -5,6 +5,11 @@
 
 pub use gettextrs::gettext;
 
+/// Marks a string for extraction without translating it, for constants translated when shown.
+pub const fn gettext_noop(msg: &'static str) -> &'static str {
+    msg
+}
+
 /// Translate a string with formatting arguments.
 /// Use this macro like: `gettext_f("Hello, {}!", &[("name", name)])`
 #[macro_export]
@@ -27,6 +27,8 @@ mod fakers;
 mod gtk_utils;
 pub mod i18n;
 mod models;
+#[cfg(test)]
+mod test_utils;
 mod widgets;
 pub use models::root_store;
 use tracing::level_filters::LevelFilter;
@@ -1,7 +1,8 @@
 use crate::{
     backends::{
-        ContainerInfo, Distrobox, Status, container_runtime::ContainerRuntime,
-        container_runtime::Usage,
+        ContainerInfo, Distrobox, StaleExport, Status,
+        container_runtime::ContainerRuntime,
+        container_runtime::{Mount, NetworkInfo, Usage},
     },
     gtk_utils::TypedListStore,
     models::{KnownDistro, known_distro_by_image},
@@ -12,10 +13,13 @@ use adw::prelude::*;
 use glib::subclass::prelude::*;
 use gtk::glib;
 use gtk::glib::{BoxedAnyObject, Properties, derived_properties};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::time::Duration;
 
+/// How long the cached app and binary lists are reused before being fetched again.
+pub const EXPORTS_MAX_AGE: Duration = Duration::from_secs(60);
+
 mod imp {
     use super::*;
 
@@ -33,10 +37,19 @@ mod imp {
         pub image: RefCell<String>,
         #[property(get, set, nullable)]
         pub distro: RefCell<Option<KnownDistro>>,
+        /// True while a task (upgrade, stop, clone...) targets this container
+        #[property(get, set)]
+        pub has_active_task: Cell<bool>,
+        /// Protected containers ask for an extra confirmation before destructive actions
+        #[property(get, set)]
+        pub protected: Cell<bool>,
         pub apps: Query<TypedListStore<glib::BoxedAnyObject>>,
         pub binaries: Query<TypedListStore<glib::BoxedAnyObject>>,
+        pub stale_exports: Query<Vec<StaleExport>>,
         // Usage statistics, without polling
         pub usage: Query<Usage>,
+        pub network_info: Query<NetworkInfo>,
+        pub mounts: Query<Vec<Mount>>,
     }
 
     impl Default for Container {
@@ -47,6 +60,8 @@ mod imp {
                 status_detail: RefCell::new(String::new()),
                 image: RefCell::new(String::new()),
                 distro: RefCell::new(None),
+                has_active_task: Cell::new(false),
+                protected: Cell::new(false),
 
                 // Fetching apps often fails when the container is not running and distrobox has to start it,
                 // so we add retries
@@ -68,7 +83,13 @@ mod imp {
                             None
                         }
                     }),
+                stale_exports: Query::new("stale_exports".into(), || async { Ok(vec![]) })
+                    .with_timeout(Duration::from_secs(10)),
                 usage: Query::new("usage".into(), || async { Ok(Usage::default()) }),
+                network_info: Query::new("network_info".into(), || async {
+                    Ok(NetworkInfo::default())
+                }),
+                mounts: Query::new("mounts".into(), || async { Ok(vec![]) }),
             }
         }
     }
@@ -105,8 +126,14 @@ impl Container {
             .set_resource_key(&format!("{container_name}:apps"));
         this.binaries()
             .set_resource_key(&format!("{container_name}:binaries"));
+        this.stale_exports()
+            .set_resource_key(&format!("{container_name}:stale_exports"));
         this.usage()
             .set_resource_key(&format!("{container_name}:usage"));
+        this.network_info()
+            .set_resource_key(&format!("{container_name}:network_info"));
+        this.mounts()
+            .set_resource_key(&format!("{container_name}:mounts"));
 
         let this_clone = this.clone();
         let apps_distrobox = distrobox.clone();
@@ -147,6 +174,16 @@ impl Container {
         });
 
         let this_clone = this.clone();
+        let stale_exports_distrobox = distrobox.clone();
+        this.stale_exports().set_fetcher(move || {
+            let this = this_clone.clone();
+            let distrobox = stale_exports_distrobox.clone();
+            async move { Ok(distrobox.list_stale_exports(&this.name()).await?) }
+        });
+
+        let this_clone = this.clone();
+        let network_runtime_query = runtime_query.clone();
+        let mounts_runtime_query = runtime_query.clone();
         let runtime_query = runtime_query.clone();
         this.usage().set_fetcher(move || {
             let this = this_clone.clone();
@@ -160,6 +197,30 @@ impl Container {
             }
         });
 
+        let this_clone = this.clone();
+        this.network_info().set_fetcher(move || {
+            let this = this_clone.clone();
+            let runtime_query = network_runtime_query.clone();
+            async move {
+                let runtime = runtime_query
+                    .data()
+                    .ok_or_else(|| anyhow::anyhow!("Container runtime not available"))?;
+                runtime.network_info(&this.name()).await
+            }
+        });
+
+        let this_clone = this.clone();
+        this.mounts().set_fetcher(move || {
+            let this = this_clone.clone();
+            let runtime_query = mounts_runtime_query.clone();
+            async move {
+                let runtime = runtime_query
+                    .data()
+                    .ok_or_else(|| anyhow::anyhow!("Container runtime not available"))?;
+                runtime.mounts(&this.name()).await
+            }
+        });
+
         this
     }
 
@@ -192,9 +253,35 @@ impl Container {
         self.imp().binaries.clone()
     }
 
+    pub fn stale_exports(&self) -> Query<Vec<StaleExport>> {
+        self.imp().stale_exports.clone()
+    }
+
     pub fn usage(&self) -> Query<Usage> {
         self.imp().usage.clone()
     }
+
+    pub fn network_info(&self) -> Query<NetworkInfo> {
+        self.imp().network_info.clone()
+    }
+
+    pub fn mounts(&self) -> Query<Vec<Mount>> {
+        self.imp().mounts.clone()
+    }
+
+    /// Loads the exportable apps, binaries and stale exports, reusing the cached lists while they are fresh.
+    pub fn load_exports(&self) {
+        self.apps().refetch_if_stale(EXPORTS_MAX_AGE);
+        self.binaries().refetch_if_stale(EXPORTS_MAX_AGE);
+        self.stale_exports().refetch_if_stale(EXPORTS_MAX_AGE);
+    }
+
+    /// Refetches the exportable apps, binaries and stale exports, ignoring the cache.
+    pub fn reload_exports(&self) {
+        self.apps().refetch();
+        self.binaries().refetch();
+        self.stale_exports().refetch();
+    }
 }
 
 impl Default for Container {
@@ -202,3 +289,47 @@ impl Default for Container {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::DistroboxCommandRunnerResponse;
+    use crate::backends::distrobox::command::default_cmd_factory;
+    use crate::test_utils::{container, spin_main_context_until};
+
+    #[gtk::test]
+    fn test_load_exports_reuses_fresh_cache() {
+        let runner = Distrobox::null_command_runner(&[
+            DistroboxCommandRunnerResponse::new_common_exported_apps(),
+        ]);
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let distrobox = Distrobox::new(runner, default_cmd_factory());
+        let runtime_query: Query<Rc<dyn ContainerRuntime>> =
+            Query::new("container_runtime".into(), || async {
+                anyhow::bail!("Container runtime not initialized")
+            });
+        let container = container(
+            &distrobox,
+            runtime_query,
+            "Ubuntu",
+            Status::Up("2 minutes ago".into()),
+            "docker.io/library/ubuntu:latest",
+        );
+
+        container.load_exports();
+        spin_main_context_until(Duration::from_secs(1), || {
+            container.apps().data().is_some() && !container.apps().is_loading()
+        });
+        let apps = container.apps().data().expect("apps should be loaded");
+        assert_eq!(apps.len(), 8);
+        let commands_after_first_load = tracker.len();
+        assert!(commands_after_first_load > 0);
+
+        // Opening the dialog again within the stale window must not re-run list_apps
+        container.load_exports();
+        spin_main_context_until(Duration::from_millis(50), || false);
+        assert!(!container.apps().is_loading());
+        assert_eq!(tracker.len(), commands_after_first_load);
+    }
+}
@@ -0,0 +1,134 @@
+/// A step of the "create and export" wizard, which walks new users from an image
+/// to a container with its apps in the host app menu.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum WizardStep {
+    #[default]
+    Creating,
+    WaitingReady,
+    LoadingApps,
+    SelectingApps,
+    Exporting,
+    Done,
+    Failed(String),
+}
+
+/// What happened while the wizard was at a step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WizardEvent {
+    /// The work of the current step finished
+    Succeeded,
+    /// The user picked the apps to export; an empty selection skips exporting
+    AppsSelected(usize),
+    Failed(String),
+}
+
+impl WizardStep {
+    pub fn transition(self, event: WizardEvent) -> WizardStep {
+        use WizardEvent as E;
+        use WizardStep as S;
+        match (self, event) {
+            (step @ (S::Done | S::Failed(_)), _) => step,
+            (_, E::Failed(error)) => S::Failed(error),
+            (S::Creating, E::Succeeded) => S::WaitingReady,
+            (S::WaitingReady, E::Succeeded) => S::LoadingApps,
+            (S::LoadingApps, E::Succeeded) => S::SelectingApps,
+            (S::SelectingApps, E::AppsSelected(0)) => S::Done,
+            (S::SelectingApps, E::AppsSelected(_)) => S::Exporting,
+            (S::Exporting, E::Succeeded) => S::Done,
+            // Events which don't belong to the current step are ignored
+            (step, _) => step,
+        }
+    }
+
+    /// Whether the step runs in the background, showing a progress page
+    pub fn is_busy(&self) -> bool {
+        matches!(
+            self,
+            WizardStep::Creating
+                | WizardStep::WaitingReady
+                | WizardStep::LoadingApps
+                | WizardStep::Exporting
+        )
+    }
+
+    /// Rough completion of the wizard, from 0.0 to 1.0
+    pub fn progress(&self) -> f64 {
+        match self {
+            WizardStep::Creating => 0.0,
+            WizardStep::WaitingReady => 0.4,
+            WizardStep::LoadingApps => 0.6,
+            WizardStep::SelectingApps => 0.7,
+            WizardStep::Exporting => 0.8,
+            WizardStep::Done | WizardStep::Failed(_) => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transitions_on_success() {
+        let mut step = WizardStep::Creating;
+        let mut visited = vec![step.clone()];
+        for event in [
+            WizardEvent::Succeeded,
+            WizardEvent::Succeeded,
+            WizardEvent::Succeeded,
+            WizardEvent::AppsSelected(2),
+            WizardEvent::Succeeded,
+        ] {
+            step = step.transition(event);
+            visited.push(step.clone());
+        }
+        assert_eq!(
+            visited,
+            vec![
+                WizardStep::Creating,
+                WizardStep::WaitingReady,
+                WizardStep::LoadingApps,
+                WizardStep::SelectingApps,
+                WizardStep::Exporting,
+                WizardStep::Done,
+            ]
+        );
+        assert!(
+            visited
+                .windows(2)
+                .all(|w| w[0].progress() <= w[1].progress())
+        );
+    }
+
+    #[test]
+    fn empty_selection_skips_exporting() {
+        let step = WizardStep::SelectingApps.transition(WizardEvent::AppsSelected(0));
+        assert_eq!(step, WizardStep::Done);
+    }
+
+    #[test]
+    fn failure_is_final() {
+        let step = WizardStep::WaitingReady.transition(WizardEvent::Failed("boom".into()));
+        assert_eq!(step, WizardStep::Failed("boom".into()));
+        assert_eq!(
+            step.clone().transition(WizardEvent::Succeeded),
+            WizardStep::Failed("boom".into())
+        );
+        assert_eq!(
+            WizardStep::Done.transition(WizardEvent::Failed("late".into())),
+            WizardStep::Done
+        );
+    }
+
+    #[test]
+    fn unexpected_events_are_ignored() {
+        assert_eq!(
+            WizardStep::Creating.transition(WizardEvent::AppsSelected(1)),
+            WizardStep::Creating
+        );
+        assert_eq!(
+            WizardStep::SelectingApps.transition(WizardEvent::Succeeded),
+            WizardStep::SelectingApps
+        );
+    }
+}
@@ -13,6 +13,8 @@ pub enum DialogType {
     TaskManager,
     Preferences,
     CommandLog,
+    AdoptContainer,
+    CreateWizard,
 }
 
 /// Parameters that can be passed when opening a dialog.
@@ -20,6 +22,10 @@ pub enum DialogType {
 pub struct DialogParams {
     /// Container to clone from (used by CreateDistrobox dialog)
     pub clone_source: Option<Container>,
+    /// Container whose volumes are copied to the new one (used by CreateDistrobox dialog)
+    pub volumes_source: Option<Container>,
+    /// Image preselected for the new container (used by CreateDistrobox dialog)
+    pub image: Option<String>,
 }
 
 impl DialogParams {
@@ -31,4 +37,14 @@ impl DialogParams {
         self.clone_source = Some(container);
         self
     }
+
+    pub fn with_volumes_source(mut self, container: Container) -> Self {
+        self.volumes_source = Some(container);
+        self
+    }
+
+    pub fn with_image(mut self, image: String) -> Self {
+        self.image = Some(image);
+        self
+    }
 }
@@ -5,12 +5,12 @@ use glib::subclass::prelude::*;
 use gtk::glib;
 use gtk::prelude::*;
 use std::cell::Ref;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::future::Future;
 use tracing::{debug, info, warn};
 use vte4::prelude::*;
 
-use crate::fakers::Child;
+use crate::fakers::{Child, Command, CommandRunnerEvent};
 use crate::widgets::TaskOutputTerminal;
 
 fn byte_stream(
@@ -29,6 +29,22 @@ fn byte_stream(
     })
 }
 
+/// Returns the commands among `events` falling in the `[start, end)` window of a task.
+/// An open window (`end == None`) covers everything recorded after `start`.
+fn commands_in_window(
+    events: &[CommandRunnerEvent],
+    start: usize,
+    end: Option<usize>,
+) -> Vec<Command> {
+    let end = end.unwrap_or(events.len()).min(events.len());
+    events
+        .get(start.min(end)..end)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|event| event.command().cloned())
+        .collect()
+}
+
 /// Status of a DistroboxTask
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, glib::Enum)]
 #[enum_type(name = "TaskStatus")]
@@ -54,9 +70,14 @@ mod imp {
         description: RefCell<String>,
         #[property(get, set, builder(TaskStatus::default()))]
         pub status: RefCell<TaskStatus>,
+        // Fraction of the work done, for tasks able to report it
+        #[property(get, set, minimum = 0.0, maximum = 1.0)]
+        pub progress: Cell<f64>,
         pub error: RefCell<Option<anyhow::Error>>, // set only if status is Failed
         pub cancellable: RefCell<Option<gtk::gio::Cancellable>>,
         pub vte_terminal: RefCell<TaskOutputTerminal>,
+        // Window of command events (indexes into the output tracker) recorded while the task ran
+        pub command_window: Cell<(usize, Option<usize>)>,
     }
 
     impl Default for DistroboxTask {
@@ -66,9 +87,11 @@ mod imp {
                 name: Default::default(),
                 description: Default::default(),
                 status: RefCell::new(TaskStatus::default()),
+                progress: Cell::new(0.0),
                 error: Default::default(),
                 cancellable: Default::default(),
                 vte_terminal: RefCell::new(TaskOutputTerminal::new()),
+                command_window: Cell::new((0, None)),
             }
         }
     }
@@ -125,8 +148,17 @@ impl DistroboxTask {
     }
 
     pub async fn handle_child_output(
+        &self,
+        child: Box<dyn Child + Send>,
+    ) -> Result<(), anyhow::Error> {
+        self.handle_child_output_with(child, |_| {}).await
+    }
+
+    /// Like `handle_child_output`, also passing every chunk of output to `on_chunk`
+    pub async fn handle_child_output_with(
         &self,
         mut child: Box<dyn Child + Send>,
+        mut on_chunk: impl FnMut(&[u8]),
     ) -> Result<(), anyhow::Error> {
         debug!("Handling child process output");
 
@@ -175,6 +207,7 @@ impl DistroboxTask {
                 chunk = merged_stream.next().fuse() => {
                     match chunk {
                         Some(Ok(bytes)) => {
+                            on_chunk(&bytes);
                             insert_chunk(bytes);
                         }
                         Some(Err(e)) => return Err(e.into()),
@@ -223,6 +256,23 @@ impl DistroboxTask {
     pub fn ended(&self) -> bool {
         self.is_failed() || self.is_successful()
     }
+    /// Resolves once the task ended, successfully or not
+    pub async fn wait_ended(&self) {
+        if self.ended() {
+            return;
+        }
+        let (tx, rx) = futures::channel::oneshot::channel();
+        let tx = RefCell::new(Some(tx));
+        let handler = self.connect_status_notify(move |task| {
+            if task.ended()
+                && let Some(tx) = tx.take()
+            {
+                let _ = tx.send(());
+            }
+        });
+        let _ = rx.await;
+        self.disconnect(handler);
+    }
     pub fn error(&self) -> Ref<'_, Option<anyhow::Error>> {
         self.imp().error.borrow()
     }
@@ -233,6 +283,24 @@ impl DistroboxTask {
         self.imp().error.borrow().as_ref().map(|e| e.to_string())
     }
 
+    /// Marks the index of the first command event belonging to this task
+    pub fn set_command_window_start(&self, index: usize) {
+        self.imp().command_window.set((index, None));
+    }
+
+    /// Marks the index after the last command event belonging to this task
+    pub fn set_command_window_end(&self, index: usize) {
+        let (start, _) = self.imp().command_window.get();
+        self.imp().command_window.set((start, Some(index)));
+    }
+
+    /// Commands executed while the task was running.
+    /// Tasks running at the same time share their window, so the list may include their commands too.
+    pub fn commands(&self, events: &[CommandRunnerEvent]) -> Vec<Command> {
+        let (start, end) = self.imp().command_window.get();
+        commands_in_window(events, start, end)
+    }
+
     /// Append text to the task output
     pub fn append_output(&self, text: &str) {
         self.imp().vte_terminal.borrow().write_output(text);
@@ -243,3 +311,38 @@ impl DistroboxTask {
         self.imp().vte_terminal.borrow().clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events() -> Vec<CommandRunnerEvent> {
+        vec![
+            CommandRunnerEvent::Started(0, Command::new_with_args("distrobox", ["ls"])),
+            CommandRunnerEvent::Output(0, Ok(())),
+            CommandRunnerEvent::Spawned(2, Command::new_with_args("distrobox", ["upgrade"])),
+            CommandRunnerEvent::Started(3, Command::new_with_args("podman", ["ps"])),
+            CommandRunnerEvent::Output(3, Ok(())),
+        ]
+    }
+
+    #[test]
+    fn commands_in_window_filters_events() {
+        let commands: Vec<String> = commands_in_window(&events(), 2, Some(4))
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(commands, ["distrobox upgrade", "podman ps"]);
+
+        // A running task sees every command recorded after its start
+        let commands: Vec<String> = commands_in_window(&events(), 1, None)
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(commands, ["distrobox upgrade", "podman ps"]);
+
+        // Out of range windows (e.g. after the log was cleared) yield nothing
+        assert!(commands_in_window(&events(), 10, Some(12)).is_empty());
+        assert!(commands_in_window(&[], 0, None).is_empty());
+    }
+}
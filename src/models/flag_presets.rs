@@ -0,0 +1,153 @@
+// Named sets of container flags and volumes, shown as toggles in the create dialog.
+// Some are built in, the others come from the `flag-presets` setting,
+// a dictionary mapping a name to space separated flags.
+
+use std::collections::HashMap;
+
+use crate::backends::{CreateArgs, InvalidValue, Volume};
+use crate::i18n::gettext_noop;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlagPreset {
+    pub name: String,
+    /// Passed to the container manager through `--additional-flags`
+    pub additional_flags: Vec<String>,
+    /// Volume descriptors, in the `host_path:container_path[:ro]` format
+    pub volumes: Vec<String>,
+}
+
+impl FlagPreset {
+    fn new(name: &str, additional_flags: &[&str], volumes: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            additional_flags: additional_flags.iter().map(|s| s.to_string()).collect(),
+            volumes: volumes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Adds the flags and volumes of the preset, skipping the ones already present
+    pub fn apply(&self, args: &mut CreateArgs) -> Result<(), InvalidValue> {
+        for flag in &self.additional_flags {
+            if !args.additional_flags.contains(flag) {
+                args.additional_flags.push(flag.clone());
+            }
+        }
+        for volume in &self.volumes {
+            let volume: Volume = volume.parse()?;
+            if !args.volumes.contains(&volume) {
+                args.volumes.push(volume);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The presets shipped with the app, their names are translated when shown
+pub fn builtin_presets() -> Vec<FlagPreset> {
+    vec![
+        FlagPreset::new(gettext_noop("GPU Passthrough"), &["--device=/dev/dri"], &[]),
+        // Debuggers and tracers need ptrace, which the default seccomp profile blocks
+        FlagPreset::new(
+            gettext_noop("Dev Tools"),
+            &["--cap-add=SYS_PTRACE", "--security-opt=seccomp=unconfined"],
+            &[],
+        ),
+        FlagPreset::new(
+            gettext_noop("Wayland Apps"),
+            &[
+                "--env=GDK_BACKEND=wayland",
+                "--env=QT_QPA_PLATFORM=wayland",
+                "--env=MOZ_ENABLE_WAYLAND=1",
+            ],
+            &[],
+        ),
+        // Lets DKMS and similar tools build modules for the host kernel
+        FlagPreset::new(
+            gettext_noop("Kernel Headers"),
+            &[],
+            &["/lib/modules:/lib/modules:ro", "/usr/src:/usr/src:ro"],
+        ),
+    ]
+}
+
+/// Builds the presets defined in the settings, sorted by name
+pub fn user_presets(entries: &HashMap<String, String>) -> Vec<FlagPreset> {
+    let mut presets: Vec<FlagPreset> = entries
+        .iter()
+        .filter(|(name, _)| !name.trim().is_empty())
+        .map(|(name, flags)| FlagPreset {
+            name: name.trim().to_string(),
+            additional_flags: flags.split_whitespace().map(|s| s.to_string()).collect(),
+            volumes: vec![],
+        })
+        .collect();
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    presets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::Distrobox;
+    use crate::backends::distrobox::command::default_cmd_factory;
+    use crate::backends::{CreateArgName, CreateArgsImage};
+    use crate::fakers::CommandRunner;
+
+    #[test]
+    fn enabling_presets_injects_flags() -> anyhow::Result<()> {
+        let presets = builtin_presets();
+        let mut args = CreateArgs {
+            name: CreateArgName::new("dev")?,
+            image: Some(CreateArgsImage::new("docker.io/library/fedora:40")?),
+            ..Default::default()
+        };
+        for preset in presets.iter().filter(|p| {
+            ["GPU Passthrough", "Dev Tools", "Kernel Headers"].contains(&p.name.as_str())
+        }) {
+            preset.apply(&mut args)?;
+        }
+        // Applying a preset twice doesn't duplicate its flags
+        presets[0].apply(&mut args)?;
+
+        let runner = CommandRunner::new_null();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let db = Distrobox::new(runner, default_cmd_factory());
+        smol::block_on(db.create(args))?;
+        let command = tracker.items()[0].command().unwrap().clone();
+        assert!(
+            command
+                .to_string()
+                .contains(" --volume /lib/modules:/lib/modules:ro --volume /usr/src:/usr/src:ro")
+        );
+        let flags_index = command
+            .args
+            .iter()
+            .position(|arg| arg == "--additional-flags")
+            .unwrap();
+        assert_eq!(
+            command.args[flags_index + 1],
+            "--device=/dev/dri --cap-add=SYS_PTRACE --security-opt=seccomp=unconfined"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn user_presets_split_flags() {
+        let entries = HashMap::from([
+            (
+                "Isolated".to_string(),
+                "--network=none  --read-only".to_string(),
+            ),
+            ("  ".to_string(), "--ignored".to_string()),
+        ]);
+        assert_eq!(
+            user_presets(&entries),
+            [FlagPreset::new(
+                "Isolated",
+                &["--network=none", "--read-only"],
+                &[]
+            )]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+// Parsing of image references and the container names derived from them
+
+use std::collections::HashSet;
+
+use crate::backends::CreateArgName;
+
+pub fn split_repo_tag_digest(s: &str) -> (&str, Option<&str>, Option<&str>) {
+    // Return (repo, tag_opt, digest_opt)
+    let last_slash = s.rfind('/');
+    if let Some(at_pos) = s.rfind('@')
+        && last_slash.is_none_or(|ls| at_pos > ls)
+    {
+        // If there is a tag before the @ (colon after last slash), strip it from repo
+        let before_at = &s[..at_pos];
+        if let Some(col_pos) = before_at.rfind(':')
+            && last_slash.is_none_or(|ls| col_pos > ls)
+        {
+            return (&before_at[..col_pos], None, Some(&s[at_pos + 1..]));
+        }
+        return (before_at, None, Some(&s[at_pos + 1..]));
+    }
+    if let Some(col_pos) = s.rfind(':')
+        && last_slash.is_none_or(|ls| col_pos > ls)
+    {
+        return (&s[..col_pos], Some(&s[col_pos + 1..]), None);
+    }
+    (s, None, None)
+}
+
+/// Whether an image reference follows the moving `latest` tag, explicitly or by omitting the tag.
+/// Such containers can't be recreated reliably, since the tag may point to a different image later.
+pub fn image_uses_latest(image: &str) -> bool {
+    match split_repo_tag_digest(image.trim()) {
+        (_, _, Some(_digest)) => false,
+        (_, Some(tag), None) => tag.eq_ignore_ascii_case("latest"),
+        (_, None, None) => true,
+    }
+}
+
+/// Derives a container name from an image reference, e.g. "docker.io/library/ubuntu:24.04" -> "ubuntu-24.04".
+/// The registry and namespace are dropped, the tag is kept to tell apart boxes of different releases.
+pub fn derive_container_name(image: &str) -> Option<CreateArgName> {
+    let (repo, tag_opt, _digest) = split_repo_tag_digest(image.trim());
+    let base = repo.rsplit('/').next().unwrap_or(repo);
+    let raw = match tag_opt {
+        Some(tag) if !tag.eq_ignore_ascii_case("latest") => format!("{base}-{tag}"),
+        _ => base.to_string(),
+    };
+
+    // Replace anything outside [a-z0-9_.-] with a single dash
+    let mut name = String::with_capacity(raw.len());
+    for c in raw.to_lowercase().chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-' {
+            c
+        } else {
+            '-'
+        };
+        if c == '-' && name.ends_with('-') {
+            continue;
+        }
+        name.push(c);
+    }
+
+    // The name must start with an alphanumeric char
+    let name = name
+        .trim_start_matches(|c: char| !c.is_ascii_alphanumeric())
+        .trim_end_matches(['-', '.']);
+    CreateArgName::new(name).ok()
+}
+
+/// Appends a number to `name` when a container already has it, e.g. "ubuntu-24.04-2"
+pub fn unique_container_name(name: CreateArgName, taken: &HashSet<String>) -> CreateArgName {
+    if !taken.contains(&name.to_string()) {
+        return name;
+    }
+    (2..)
+        .map(|i| format!("{name}-{i}"))
+        .find(|candidate| !taken.contains(candidate))
+        .and_then(|candidate| CreateArgName::new(&candidate).ok())
+        .unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{
+        derive_container_name, image_uses_latest, split_repo_tag_digest, unique_container_name,
+    };
+
+    #[test]
+    fn split_repo_tag_digest_examples() {
+        assert_eq!(
+            split_repo_tag_digest("repo:1.2.3"),
+            ("repo", Some("1.2.3"), None)
+        );
+        assert_eq!(
+            split_repo_tag_digest("repo@sha256:abcdef"),
+            ("repo", None, Some("sha256:abcdef"))
+        );
+        assert_eq!(
+            split_repo_tag_digest("host:5000/repo:1.0"),
+            ("host:5000/repo", Some("1.0"), None)
+        );
+        assert_eq!(
+            split_repo_tag_digest("host:5000/repo@sha256:abc"),
+            ("host:5000/repo", None, Some("sha256:abc"))
+        );
+        assert_eq!(
+            split_repo_tag_digest("repo:tag@sha256:abc"),
+            ("repo", None, Some("sha256:abc"))
+        );
+    }
+
+    #[test]
+    fn derive_container_name_examples() {
+        let name = derive_container_name("docker.io/library/ubuntu:24.04").unwrap();
+        assert_eq!(name.to_string(), "ubuntu-24.04");
+
+        let name = derive_container_name("quay.io/toolbx/arch-toolbox:latest").unwrap();
+        assert_eq!(name.to_string(), "arch-toolbox");
+
+        let name = derive_container_name("ghcr.io/ublue-os/fedora-toolbox@sha256:abc").unwrap();
+        assert_eq!(name.to_string(), "fedora-toolbox");
+
+        let name = derive_container_name("localhost:5000/My+Image:Edge 1").unwrap();
+        assert_eq!(name.to_string(), "my-image-edge-1");
+
+        assert!(derive_container_name("").is_none());
+        assert!(derive_container_name("docker.io/library/").is_none());
+    }
+
+    #[test]
+    fn unique_container_name_skips_taken_names() {
+        let name = derive_container_name("docker.io/library/ubuntu:24.04").unwrap();
+        let taken = HashSet::from(["ubuntu-24.04".to_string(), "ubuntu-24.04-2".to_string()]);
+        assert_eq!(
+            unique_container_name(name.clone(), &taken).to_string(),
+            "ubuntu-24.04-3"
+        );
+        assert_eq!(
+            unique_container_name(name, &HashSet::new()).to_string(),
+            "ubuntu-24.04"
+        );
+    }
+
+    #[test]
+    fn split_repo_tag_digest_more_cases() {
+        // triple combo: repo:tag@sha256: -> digest should win, tag discarded
+        assert_eq!(
+            split_repo_tag_digest("repo:tag@sha256:abc"),
+            ("repo", None, Some("sha256:abc"))
+        );
+        // host with port, tag and digest -> digest wins and repo includes host:port/repo
+        assert_eq!(
+            split_repo_tag_digest("example.com:5000/repo:1.0@sha256:abc"),
+            ("example.com:5000/repo", None, Some("sha256:abc"))
+        );
+        // plain repo no tag/digest
+        assert_eq!(
+            split_repo_tag_digest("plainrepo"),
+            ("plainrepo", None, None)
+        );
+    }
+
+    #[test]
+    fn image_uses_latest_examples() {
+        assert!(image_uses_latest("ubuntu:latest"));
+        // No tag means the runtime pulls latest
+        assert!(image_uses_latest("ubuntu"));
+        assert!(image_uses_latest("localhost:5000/ubuntu"));
+        assert!(!image_uses_latest("ubuntu:24.04"));
+        assert!(!image_uses_latest("docker.io/library/ubuntu@sha256:abcdef"));
+    }
+}
@@ -8,6 +8,8 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use crate::fakers::Command;
+use crate::i18n::gettext_noop;
+use crate::models::image_reference::split_repo_tag_digest;
 
 fn build_distros() -> HashMap<String, KnownDistro> {
     [
@@ -124,6 +126,333 @@ fn zypper_install_cmd(file: &Path) -> Command {
     cmd
 }
 
+// Desktop files worth exporting from any container: editors and terminal tools,
+// the apps usually installed in dev containers.
+const COMMON_RECOMMENDED_APPS: [&str; 9] = [
+    "code.desktop",
+    "codium.desktop",
+    "org.gnome.TextEditor.desktop",
+    "org.kde.kate.desktop",
+    "emacs.desktop",
+    "gvim.desktop",
+    "nvim.desktop",
+    "htop.desktop",
+    "btop.desktop",
+];
+
+// The graphical package manager of each distro, if it has one
+fn distro_recommended_apps(distro_name: &str) -> &'static [&'static str] {
+    match distro_name {
+        "debian" | "ubuntu" | "mint" | "kali" | "neon" | "deepin" => &["synaptic.desktop"],
+        "fedora" | "alma" | "centos" | "rocky" | "redhat" | "rhel" | "oracle" | "mageia" => {
+            &["dnfdragora.desktop"]
+        }
+        "opensuse" => &["org.opensuse.yast.SwSingle.desktop"],
+        "arch" => &["octopi.desktop"],
+        _ => &[],
+    }
+}
+
+/// A distro offered in the create menu, created in one click from a known-good image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickCreateEntry {
+    /// Name of the distro among the known ones
+    pub distro: &'static str,
+    pub label: &'static str,
+    pub image: &'static str,
+}
+
+pub const QUICK_CREATE_ENTRIES: &[QuickCreateEntry] = &[
+    QuickCreateEntry {
+        distro: "ubuntu",
+        label: "Ubuntu 24.04",
+        image: "docker.io/library/ubuntu:24.04",
+    },
+    QuickCreateEntry {
+        distro: "fedora",
+        label: "Fedora",
+        image: "registry.fedoraproject.org/fedora-toolbox:latest",
+    },
+    QuickCreateEntry {
+        distro: "debian",
+        label: "Debian Stable",
+        image: "docker.io/library/debian:stable",
+    },
+    QuickCreateEntry {
+        distro: "arch",
+        label: "Arch Linux",
+        image: "docker.io/library/archlinux:latest",
+    },
+    QuickCreateEntry {
+        distro: "alpine",
+        label: "Alpine",
+        image: "docker.io/library/alpine:latest",
+    },
+    QuickCreateEntry {
+        distro: "opensuse",
+        label: "openSUSE Tumbleweed",
+        image: "registry.opensuse.org/opensuse/tumbleweed:latest",
+    },
+];
+
+pub fn quick_create_entry(distro: &str) -> Option<QuickCreateEntry> {
+    QUICK_CREATE_ENTRIES
+        .iter()
+        .find(|entry| entry.distro == distro)
+        .copied()
+}
+
+/// Related distros, used to browse the images by what they're based on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DistroFamily {
+    Debian,
+    Rpm,
+    Arch,
+    Alpine,
+    /// Distros not based on another one
+    Independent,
+    /// Images not in the catalog
+    Other,
+}
+
+/// A distro of the image catalog, describing its images in the image picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogDistro {
+    /// Name of the distro among the known ones
+    pub distro: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub family: DistroFamily,
+}
+
+const fn catalog_distro(
+    distro: &'static str,
+    name: &'static str,
+    description: &'static str,
+    family: DistroFamily,
+) -> CatalogDistro {
+    CatalogDistro {
+        distro,
+        name,
+        description,
+        family,
+    }
+}
+
+// An image belongs to the first distro named in its repository, so the more generic
+// names (like "arch") come last
+pub const IMAGE_CATALOG: &[CatalogDistro] = &[
+    catalog_distro(
+        "ubuntu",
+        "Ubuntu",
+        gettext_noop("Popular and widely supported, with long term support releases"),
+        DistroFamily::Debian,
+    ),
+    catalog_distro(
+        "debian",
+        "Debian",
+        gettext_noop("Stable and conservative, the base of many distros"),
+        DistroFamily::Debian,
+    ),
+    catalog_distro(
+        "mint",
+        "Linux Mint",
+        gettext_noop("Ubuntu based, focused on ease of use"),
+        DistroFamily::Debian,
+    ),
+    catalog_distro(
+        "kali",
+        "Kali Linux",
+        gettext_noop("Tools for security testing"),
+        DistroFamily::Debian,
+    ),
+    catalog_distro(
+        "neon",
+        "KDE neon",
+        gettext_noop("Ubuntu with the latest KDE software"),
+        DistroFamily::Debian,
+    ),
+    catalog_distro(
+        "deepin",
+        "Deepin",
+        gettext_noop("Debian based, with its own desktop"),
+        DistroFamily::Debian,
+    ),
+    catalog_distro(
+        "vanilla",
+        "Vanilla OS",
+        gettext_noop("Debian based, immutable by design"),
+        DistroFamily::Debian,
+    ),
+    catalog_distro(
+        "fedora",
+        "Fedora",
+        gettext_noop("Up to date software, sponsored by Red Hat"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "centos",
+        "CentOS Stream",
+        gettext_noop("The upcoming Red Hat Enterprise Linux release"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "rhel",
+        "Red Hat Enterprise Linux",
+        gettext_noop("Commercial distro with long term support"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "redhat",
+        "Red Hat Enterprise Linux",
+        gettext_noop("Commercial distro with long term support"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "alma",
+        "AlmaLinux",
+        gettext_noop("Community rebuild of Red Hat Enterprise Linux"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "rocky",
+        "Rocky Linux",
+        gettext_noop("Community rebuild of Red Hat Enterprise Linux"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "oracle",
+        "Oracle Linux",
+        gettext_noop("Red Hat Enterprise Linux compatible, by Oracle"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "amazon",
+        "Amazon Linux",
+        gettext_noop("Made for the Amazon Web Services cloud"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "mageia",
+        "Mageia",
+        gettext_noop("Community distro descending from Mandriva"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "opensuse",
+        "openSUSE",
+        gettext_noop("Rolling Tumbleweed or stable Leap releases"),
+        DistroFamily::Rpm,
+    ),
+    catalog_distro(
+        "crystal",
+        "Crystal Linux",
+        gettext_noop("Arch based, with its own tools"),
+        DistroFamily::Arch,
+    ),
+    catalog_distro(
+        "arch",
+        "Arch Linux",
+        gettext_noop("Rolling release with the latest software and the AUR"),
+        DistroFamily::Arch,
+    ),
+    catalog_distro(
+        "alpine",
+        "Alpine Linux",
+        gettext_noop("Small and simple, built on musl"),
+        DistroFamily::Alpine,
+    ),
+    catalog_distro(
+        "gentoo",
+        "Gentoo",
+        gettext_noop("Packages built from source, highly customizable"),
+        DistroFamily::Independent,
+    ),
+    catalog_distro(
+        "void",
+        "Void Linux",
+        gettext_noop("Rolling release, independently developed"),
+        DistroFamily::Independent,
+    ),
+    catalog_distro(
+        "slackware",
+        "Slackware",
+        gettext_noop("One of the oldest distros, kept simple"),
+        DistroFamily::Independent,
+    ),
+    catalog_distro(
+        "clearlinux",
+        "Clear Linux",
+        gettext_noop("Optimized for Intel hardware"),
+        DistroFamily::Independent,
+    ),
+];
+
+/// An image of the catalog, with a friendly title like "Ubuntu 24.04"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogImage {
+    pub image: String,
+    pub title: String,
+    pub description: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogGroup {
+    pub family: DistroFamily,
+    pub images: Vec<CatalogImage>,
+}
+
+pub fn catalog_distro_by_image(image: &str) -> Option<&'static CatalogDistro> {
+    // The tag can't name the distro, but the registry can (registry.opensuse.org/opensuse/leap)
+    let (repo, _tag, _digest) = split_repo_tag_digest(image);
+    let repo = repo.to_lowercase();
+    IMAGE_CATALOG
+        .iter()
+        .find(|entry| repo.contains(entry.distro))
+}
+
+pub fn catalog_image(image: &str) -> CatalogImage {
+    let Some(entry) = catalog_distro_by_image(image) else {
+        return CatalogImage {
+            image: image.to_string(),
+            title: image.to_string(),
+            description: None,
+        };
+    };
+    let title = match split_repo_tag_digest(image) {
+        (_, Some(tag), _) if !tag.eq_ignore_ascii_case("latest") => {
+            format!("{} {tag}", entry.name)
+        }
+        _ => entry.name.to_string(),
+    };
+    CatalogImage {
+        image: image.to_string(),
+        title,
+        description: Some(entry.description),
+    }
+}
+
+/// Groups the images by distro family, keeping their order inside each group.
+/// Images of unknown distros end up in the `Other` group, the last one.
+pub fn group_images_by_family(images: &[String]) -> Vec<CatalogGroup> {
+    let mut groups: Vec<CatalogGroup> = vec![];
+    for image in images {
+        let family = catalog_distro_by_image(image)
+            .map(|entry| entry.family)
+            .unwrap_or(DistroFamily::Other);
+        let image = catalog_image(image);
+        match groups.iter_mut().find(|group| group.family == family) {
+            Some(group) => group.images.push(image),
+            None => groups.push(CatalogGroup {
+                family,
+                images: vec![image],
+            }),
+        }
+    }
+    groups.sort_by_key(|group| group.family);
+    groups
+}
+
 pub fn known_distro_by_image(url: &str) -> Option<KnownDistro> {
     with_distros(|distros| {
         distros
@@ -197,6 +526,25 @@ impl KnownDistro {
     pub fn default_icon_name() -> &'static str {
         "tux-symbolic"
     }
+    /// Desktop file names suggested for export from a container of this distro
+    pub fn recommended_apps(&self) -> Vec<&'static str> {
+        distro_recommended_apps(&self.name())
+            .iter()
+            .chain(COMMON_RECOMMENDED_APPS.iter())
+            .copied()
+            .collect()
+    }
+    /// Keeps the recommended ones among the given desktop file names
+    pub fn filter_recommended<'a>(
+        &self,
+        desktop_file_names: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<&'a str> {
+        let recommended = self.recommended_apps();
+        desktop_file_names
+            .into_iter()
+            .filter(|name| recommended.contains(name))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +552,15 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_quick_create_entries_are_known_distros() {
+        for entry in QUICK_CREATE_ENTRIES {
+            let distro = known_distro_by_image(entry.image);
+            assert!(distro.is_some(), "{} isn't a known distro", entry.image);
+            assert!(with_distros(|distros| distros.contains_key(entry.distro)));
+        }
+    }
+
     #[test]
     fn test_package_manager_installable_file() {
         assert_eq!(PackageManager::Apt.installable_file(), Some(".deb"));
@@ -328,8 +685,86 @@ mod tests {
         assert_eq!(pm, PackageManager::Unknown);
     }
 
+    #[test]
+    fn test_recommended_apps_filtered_against_available() {
+        let ubuntu = known_distro_by_image("docker.io/library/ubuntu:24.04").unwrap();
+        let recommended = ubuntu.recommended_apps();
+        assert_eq!(recommended[0], "synaptic.desktop");
+        assert!(recommended.contains(&"code.desktop"));
+
+        let available = [
+            "code.desktop",
+            "firefox.desktop",
+            "synaptic.desktop",
+            "dnfdragora.desktop",
+        ];
+        assert_eq!(
+            ubuntu.filter_recommended(available),
+            ["code.desktop", "synaptic.desktop"]
+        );
+
+        let fedora = known_distro_by_image("registry.fedoraproject.org/fedora:40").unwrap();
+        assert_eq!(
+            fedora.filter_recommended(available),
+            ["code.desktop", "dnfdragora.desktop"]
+        );
+    }
+
     #[test]
     fn test_known_distro_default_icon_name() {
         assert_eq!(KnownDistro::default_icon_name(), "tux-symbolic");
     }
+
+    #[test]
+    fn test_group_images_by_family() {
+        let images: Vec<String> = [
+            "quay.io/toolbx/ubuntu-toolbox:24.04",
+            "registry.fedoraproject.org/fedora-toolbox:40",
+            "docker.io/library/archlinux:latest",
+            "docker.io/library/debian:stable",
+            "registry.opensuse.org/opensuse/tumbleweed:latest",
+            "docker.io/library/alpine:3.20",
+            "ghcr.io/example/custom-image:1.0",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        let groups = group_images_by_family(&images);
+        let families: Vec<(DistroFamily, Vec<&str>)> = groups
+            .iter()
+            .map(|group| {
+                let titles = group.images.iter().map(|i| i.title.as_str()).collect();
+                (group.family, titles)
+            })
+            .collect();
+        assert_eq!(
+            families,
+            [
+                (DistroFamily::Debian, vec!["Ubuntu 24.04", "Debian stable"]),
+                (DistroFamily::Rpm, vec!["Fedora 40", "openSUSE"]),
+                (DistroFamily::Arch, vec!["Arch Linux"]),
+                (DistroFamily::Alpine, vec!["Alpine Linux 3.20"]),
+                (
+                    DistroFamily::Other,
+                    vec!["ghcr.io/example/custom-image:1.0"]
+                ),
+            ]
+        );
+        // Uncategorized images keep their raw name and have no description
+        let other = groups.last().unwrap();
+        assert_eq!(other.images[0].description, None);
+    }
+
+    #[test]
+    fn test_image_catalog_covers_known_distros() {
+        with_distros(|distros| {
+            for name in distros.keys() {
+                assert!(
+                    IMAGE_CATALOG.iter().any(|entry| &entry.distro == name),
+                    "{name} is missing from the catalog"
+                );
+            }
+        });
+    }
 }
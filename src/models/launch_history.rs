@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+/// How many recently launched apps are remembered for each container
+pub const LAUNCH_HISTORY_CAP: usize = 5;
+
+/// An app launched recently, identified by its desktop file inside the container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentApp {
+    pub desktop_file_path: String,
+    pub name: String,
+}
+
+/// The recently launched apps of each container, newest first.
+/// Stored in the `launch-history` setting, as a map of `(desktop file path, name)` lists.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LaunchHistory {
+    apps: HashMap<String, Vec<RecentApp>>,
+}
+
+impl LaunchHistory {
+    pub fn from_map(map: HashMap<String, Vec<(String, String)>>) -> Self {
+        let apps = map
+            .into_iter()
+            .map(|(container, apps)| {
+                let apps = apps
+                    .into_iter()
+                    .map(|(desktop_file_path, name)| RecentApp {
+                        desktop_file_path,
+                        name,
+                    })
+                    .collect();
+                (container, apps)
+            })
+            .collect();
+        Self { apps }
+    }
+
+    pub fn to_map(&self) -> HashMap<String, Vec<(String, String)>> {
+        self.apps
+            .iter()
+            .map(|(container, apps)| {
+                let apps = apps
+                    .iter()
+                    .map(|app| (app.desktop_file_path.clone(), app.name.clone()))
+                    .collect();
+                (container.clone(), apps)
+            })
+            .collect()
+    }
+
+    /// Moves the app to the front of the history of the container, forgetting the oldest
+    /// apps past LAUNCH_HISTORY_CAP
+    pub fn record(&mut self, container: &str, app: RecentApp) {
+        let apps = self.apps.entry(container.to_string()).or_default();
+        apps.retain(|recent| recent.desktop_file_path != app.desktop_file_path);
+        apps.insert(0, app);
+        apps.truncate(LAUNCH_HISTORY_CAP);
+    }
+
+    /// Drops the history of a container, e.g. once it's deleted
+    pub fn forget(&mut self, container: &str) {
+        self.apps.remove(container);
+    }
+
+    pub fn recent(&self, container: &str) -> &[RecentApp] {
+        self.apps
+            .get(container)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str) -> RecentApp {
+        RecentApp {
+            desktop_file_path: format!("/usr/share/applications/{name}.desktop"),
+            name: name.into(),
+        }
+    }
+
+    fn names<'a>(history: &'a LaunchHistory, container: &str) -> Vec<&'a str> {
+        history
+            .recent(container)
+            .iter()
+            .map(|app| app.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn records_launches_newest_first() {
+        let mut history = LaunchHistory::default();
+        history.record("ubuntu", app("firefox"));
+        history.record("ubuntu", app("gimp"));
+        history.record("fedora", app("code"));
+        // Launching an app again moves it to the front, without duplicating it
+        history.record("ubuntu", app("firefox"));
+
+        assert_eq!(names(&history, "ubuntu"), ["firefox", "gimp"]);
+        assert_eq!(names(&history, "fedora"), ["code"]);
+        assert!(history.recent("arch").is_empty());
+        assert_eq!(LaunchHistory::from_map(history.to_map()), history);
+    }
+
+    #[test]
+    fn trims_to_the_cap() {
+        let mut history = LaunchHistory::default();
+        for i in 0..LAUNCH_HISTORY_CAP + 2 {
+            history.record("ubuntu", app(&format!("app{i}")));
+        }
+
+        let recent = names(&history, "ubuntu");
+        assert_eq!(recent.len(), LAUNCH_HISTORY_CAP);
+        assert_eq!(recent.first(), Some(&"app6"));
+        assert_eq!(recent.last(), Some(&"app2"));
+    }
+
+    #[test]
+    fn forgets_a_container() {
+        let mut history = LaunchHistory::default();
+        history.record("ubuntu", app("firefox"));
+        history.record("fedora", app("code"));
+        history.forget("ubuntu");
+
+        assert!(history.recent("ubuntu").is_empty());
+        assert_eq!(names(&history, "fedora"), ["code"]);
+        assert!(!history.to_map().contains_key("ubuntu"));
+    }
+}
@@ -1,16 +1,25 @@
 pub mod container;
 pub mod container_sort_key;
+pub mod create_wizard;
 pub mod dialog_type;
 pub mod distrobox_task;
+pub mod flag_presets;
+pub mod image_reference;
 pub mod known_distros;
+pub mod launch_history;
+pub mod notification;
 pub mod root_store;
 pub mod tagged_object;
 pub mod view_type;
 
 pub use container::Container;
 pub use container_sort_key::ContainerSortKey;
+pub use create_wizard::{WizardEvent, WizardStep};
 pub use dialog_type::{DialogParams, DialogType};
 pub use distrobox_task::{DistroboxTask, TaskStatus};
+pub use flag_presets::FlagPreset;
 pub use known_distros::{KnownDistro, known_distro_by_image};
+pub use launch_history::{LaunchHistory, RecentApp};
+pub use notification::{Notification, NotificationLevel};
 pub use root_store::RootStore;
 pub use view_type::ViewType;
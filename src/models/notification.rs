@@ -0,0 +1,73 @@
+use glib::Properties;
+use glib::prelude::*;
+use glib::subclass::prelude::*;
+use gtk::glib;
+use std::cell::{Cell, RefCell};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "NotificationLevel")]
+pub enum NotificationLevel {
+    #[default]
+    Error,
+    Warning,
+}
+
+mod imp {
+    use super::*;
+
+    // An error or warning kept in the notifications center, after its toast is gone
+    #[derive(Properties, Default)]
+    #[properties(wrapper_type = super::Notification)]
+    pub struct Notification {
+        #[property(get, set)]
+        title: RefCell<String>,
+        #[property(get, set)]
+        message: RefCell<String>,
+        #[property(get, set, builder(NotificationLevel::default()))]
+        level: Cell<NotificationLevel>,
+        /// Unix timestamp, in seconds
+        #[property(get, set)]
+        created_at: Cell<i64>,
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Notification {}
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Notification {
+        const NAME: &'static str = "Notification";
+        type Type = super::Notification;
+    }
+}
+
+glib::wrapper! {
+    pub struct Notification(ObjectSubclass<imp::Notification>);
+}
+
+impl Notification {
+    pub fn new(level: NotificationLevel, title: &str, message: &str) -> Self {
+        let created_at = glib::DateTime::now_utc()
+            .map(|now| now.to_unix())
+            .unwrap_or_default();
+        glib::Object::builder()
+            .property("level", level)
+            .property("title", title)
+            .property("message", message)
+            .property("created-at", created_at)
+            .build()
+    }
+
+    /// The local time of the notification, e.g. "14:05"
+    pub fn time_label(&self) -> String {
+        glib::DateTime::from_unix_local(self.created_at())
+            .and_then(|time| time.format("%H:%M"))
+            .map(|time| time.to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for Notification {
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
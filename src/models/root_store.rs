@@ -7,8 +7,8 @@ use gtk::prelude::*;
 use gtk::{gio, glib};
 use std::cell::OnceCell;
 use std::cell::RefCell;
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::time::Duration;
 use tracing::error;
@@ -17,24 +17,33 @@ use tracing::{debug, warn};
 
 use crate::backends::Distrobox;
 use crate::backends::Status;
-use crate::backends::container_runtime::{ContainerRuntime, get_container_runtime};
+use crate::backends::container_runtime::{
+    ContainerRuntime, RuntimeContainer, get_container_runtime,
+};
 use crate::backends::podman::PodmanEvent;
 use crate::backends::supported_terminals::{Terminal, TerminalRepository};
-use crate::backends::{self, CreateArgs, ExportableApp};
+use crate::backends::{self, CreateArgs, ExportableApp, LaunchSession, StaleExport};
 use crate::fakers::{Command, CommandRunner, FdMode};
+use crate::gettext_f;
 use crate::gtk_utils::{TypedListStore, reconcile_list_by_key};
+use crate::i18n::gettext;
 use crate::models::DistroboxTask;
 use crate::models::ViewType;
+use crate::models::image_reference::{derive_container_name, unique_container_name};
+use crate::models::known_distros::quick_create_entry;
 use crate::models::{Container, ContainerSortKey};
 use crate::models::{DialogParams, DialogType};
+use crate::models::{LaunchHistory, Notification, NotificationLevel, RecentApp};
 use crate::query::Query;
 
 use serde::Deserialize;
 
-const SHORTCUT_DEFINITIONS: [(&str, &str); 13] = [
+const SHORTCUT_DEFINITIONS: [(&str, &str); 15] = [
     ("<primary>q", "app.quit"),
     ("<primary>question", "app.shortcuts"),
     ("F5", "win.refresh"),
+    ("F9", "win.toggle-sidebar"),
+    ("<primary>r", "win.refresh-all"),
     ("<primary>u", "win.upgrade-container"),
     ("<primary><shift>u", "win.upgrade-all"),
     ("<primary>i", "win.install-package"),
@@ -47,6 +56,33 @@ const SHORTCUT_DEFINITIONS: [(&str, &str); 13] = [
     ("<primary>d", "win.delete-container"),
 ];
 
+// Older notifications are dropped from the notifications center
+const MAX_NOTIFICATIONS: u32 = 50;
+
+/// Settings holding the timeout, in seconds, of each kind of query
+const QUERY_TIMEOUT_KEYS: [&str; 3] = ["version-timeout", "list-timeout", "images-timeout"];
+
+const TERMINAL_CHECK_TEXT: &str = "DistroShelf terminal validation";
+
+// Written by the terminal validation command. It lives in the cache directory because,
+// unlike /tmp, it's shared between the host and the flatpak sandbox.
+fn terminal_check_marker_path() -> PathBuf {
+    glib::user_cache_dir().join("distroshelf-terminal-check")
+}
+
+/// Returned when a destructive action targets a protected container without the extra confirmation
+#[derive(Debug, thiserror::Error)]
+#[error("{0} is protected, type its name to confirm")]
+pub struct ProtectedContainerError(pub String);
+
+/// Looks up the default command of a container, ignoring blank ones
+fn default_command_for<'a>(commands: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    commands
+        .get(name)
+        .map(|command| command.trim())
+        .filter(|command| !command.is_empty())
+}
+
 #[derive(Debug, Clone, Deserialize, Hash, Eq, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Image {
@@ -90,6 +126,9 @@ mod imp {
         #[property(get, set, nullable)]
         pub selected_task: RefCell<Option<DistroboxTask>>,
 
+        /// Recent errors and warnings, newest first
+        pub notifications: TypedListStore<Notification>,
+
         #[property(get)]
         pub settings: gio::Settings,
 
@@ -104,6 +143,18 @@ mod imp {
         #[property(get, set)]
         bundled_update_available: std::cell::Cell<bool>,
 
+        /// True while any of the queries refreshed by `refresh_all` is loading
+        #[property(get)]
+        refreshing: std::cell::Cell<bool>,
+
+        /// Mirrors the `offline-mode` setting: nothing is downloaded while it's enabled
+        #[property(get, set)]
+        offline_mode: std::cell::Cell<bool>,
+
+        /// True when the containers list is refreshed by polling, as podman events are unavailable
+        #[property(get)]
+        polling_containers: std::cell::Cell<bool>,
+
         /// Parameters for the current dialog (not a GObject property)
         pub dialog_params: RefCell<DialogParams>,
     }
@@ -137,7 +188,11 @@ mod imp {
                 containers_query: Query::new("containers".into(), || async { Ok(vec![]) }),
                 tasks: TypedListStore::new(),
                 selected_task: Default::default(),
+                notifications: TypedListStore::new(),
                 bundled_update_available: std::cell::Cell::new(false),
+                refreshing: std::cell::Cell::new(false),
+                offline_mode: std::cell::Cell::new(false),
+                polling_containers: std::cell::Cell::new(false),
                 settings: gio::Settings::new("com.ranfdev.DistroShelf"),
                 shortcuts: gio::ListStore::new::<gtk::Shortcut>(),
                 shortcuts_enabled: std::cell::Cell::new(false),
@@ -153,28 +208,26 @@ mod imp {
 
             // Watch settings
             let settings = obj.settings();
-            settings.connect_changed(
-                Some("distrobox-executable"),
-                glib::clone!(
-                    #[weak]
-                    obj,
-                    move |settings, _key| {
-                        let val = settings.string("distrobox-executable");
-                        if val == "bundled" {
-                            if crate::distrobox_downloader::resolve_bundled_distrobox_path()
-                                .is_none()
-                            {
-                                obj.download_distrobox();
-                            } else {
-                                obj.distrobox_version().refetch();
-                            }
-                        } else {
-                            obj.distrobox_version().refetch();
-                        }
-                        obj.update_bundled_update_available();
-                    }
-                ),
-            );
+            // Only read, so that tests can toggle the property without touching the settings
+            settings
+                .bind("offline-mode", &*obj, "offline-mode")
+                .get()
+                .build();
+            obj.connect_offline_mode_notify(|obj| obj.update_bundled_update_available());
+            for key in [
+                "distrobox-executable",
+                "distrobox-pinned-version",
+                "distrobox-pinned-sha256",
+            ] {
+                settings.connect_changed(
+                    Some(key),
+                    glib::clone!(
+                        #[weak]
+                        obj,
+                        move |_settings, _key| obj.apply_distrobox_executable()
+                    ),
+                );
+            }
         }
     }
 
@@ -244,7 +297,8 @@ impl RootStore {
         let cmd_factory: crate::backends::distrobox::command::CmdFactory = Rc::new(move || {
             let distrobox_executable_val = this_clone.settings().string("distrobox-executable");
             let selected_program: String = if distrobox_executable_val == "bundled" {
-                crate::distrobox_downloader::resolve_bundled_distrobox_path()
+                let version = this_clone.bundled_distrobox_version();
+                crate::distrobox_downloader::resolve_bundled_distrobox_path(&version)
                     .map(|p| p.to_string_lossy().into_owned())
                     .unwrap_or_else(|| "distrobox".into())
             } else {
@@ -393,13 +447,70 @@ impl RootStore {
                 |item| item.name(),
                 &["status-tag", "status-detail", "distro", "image"],
             );
+            this.update_active_tasks();
+            this.update_protected_containers();
         });
 
+        let this_clone = this.clone();
+        this.settings()
+            .connect_changed(Some("protected-containers"), move |_, _| {
+                this_clone.update_protected_containers();
+            });
+
+        this.apply_query_timeouts();
+        for key in QUERY_TIMEOUT_KEYS {
+            let this_clone = this.clone();
+            this.settings()
+                .connect_changed(Some(key), move |_, _| this_clone.apply_query_timeouts());
+        }
+
+        this.notify_query_errors(
+            &this.containers_query(),
+            &gettext("Failed to load containers"),
+        );
+        this.notify_query_errors(&this.images_query(), &gettext("Failed to load images"));
+        this.notify_query_errors(
+            &this.downloaded_images_query(),
+            &gettext("Failed to load downloaded images"),
+        );
+        this.notify_query_errors(
+            &this.container_runtime(),
+            &gettext("No container runtime found"),
+        );
+        this.notify_query_errors(
+            &this.distrobox_version(),
+            &gettext("Failed to run distrobox"),
+        );
+
+        let this_clone = this.clone();
+        let update_refreshing = Rc::new(move || this_clone.update_refreshing());
+        let f = update_refreshing.clone();
+        this.containers_query().connect_loading(move |_| f());
+        let f = update_refreshing.clone();
+        this.images_query().connect_loading(move |_| f());
+        let f = update_refreshing.clone();
+        this.downloaded_images_query().connect_loading(move |_| f());
+        let f = update_refreshing.clone();
+        this.container_runtime().connect_loading(move |_| f());
+        let f = update_refreshing.clone();
+        this.distrobox_version().connect_loading(move |_| f());
+
         this.enable_shortcuts();
 
         this
     }
 
+    fn update_refreshing(&self) {
+        let refreshing = self.containers_query().is_loading()
+            || self.images_query().is_loading()
+            || self.downloaded_images_query().is_loading()
+            || self.container_runtime().is_loading()
+            || self.distrobox_version().is_loading();
+        if self.imp().refreshing.replace(refreshing) != refreshing {
+            self.notify_refreshing();
+        }
+    }
+
     fn build_shortcut(trigger: &str, action: &str) -> Option<gtk::Shortcut> {
         let trigger =
             gtk::ShortcutTrigger::parse_string(trigger).expect("Invalid shortcut trigger");
@@ -569,16 +680,120 @@ impl RootStore {
         self.containers_query().refetch();
     }
 
+    /// Refetches the containers, the images and the runtime/distrobox information.
+    /// Progress is reported through the `refreshing` property.
+    pub fn refresh_all(&self) {
+        self.container_runtime().refetch();
+        self.distrobox_version().refetch();
+        self.containers_query().refetch();
+        self.images_query().refetch();
+        self.downloaded_images_query().refetch();
+    }
+
     /// Recalculates and sets the `bundled_update_available` property.
     /// Should be called after distrobox_version query completes, after a download, or when
     /// the distrobox-executable setting changes.
     pub fn update_bundled_update_available(&self) {
         let settings_val = self.settings().string("distrobox-executable");
-        let available =
-            settings_val == "bundled" && crate::distrobox_downloader::is_bundled_update_available();
+        // Updating means downloading the new version
+        let available = settings_val == "bundled"
+            && !self.offline_mode()
+            && crate::distrobox_downloader::is_bundled_update_available(
+                &self.bundled_distrobox_version(),
+            );
         self.set_bundled_update_available(available);
     }
 
+    /// Switches back to the host distrobox when "bundled" is selected but the bundled executable
+    /// can't be run, e.g. because its download failed. Returns whether the setting was reverted.
+    pub fn revert_unresolved_bundled_distrobox(
+        &self,
+        bundled_path: Option<PathBuf>,
+        download_error: Option<String>,
+    ) -> bool {
+        let settings = self.settings();
+        if settings.string("distrobox-executable") != "bundled" {
+            return false;
+        }
+        if bundled_path
+            .as_deref()
+            .is_some_and(crate::distrobox_downloader::is_executable)
+        {
+            return false;
+        }
+        let reason = match (download_error, bundled_path) {
+            (Some(error), _) => format!("The download failed: {error}"),
+            (None, Some(path)) => format!("{} is missing or not executable", path.display()),
+            (None, None) => "No bundled distrobox was found".to_string(),
+        };
+        warn!(%reason, "Bundled distrobox unavailable, reverting to the host one");
+        if let Err(e) = settings.set_string("distrobox-executable", "host") {
+            error!(error = %e, "Failed to revert the distrobox executable setting");
+        }
+        self.push_notification(
+            NotificationLevel::Error,
+            &gettext("Bundled Distrobox Unavailable"),
+            &gettext_f!(
+                "{reason}. Switched back to the distrobox installed on the system.",
+                "reason" => reason
+            ),
+        );
+        true
+    }
+
+    /// The version of the bundled distrobox: the pinned one if set, or the default one
+    pub fn bundled_distrobox_version(&self) -> String {
+        let pinned = self.settings().string("distrobox-pinned-version");
+        if pinned.is_empty() {
+            crate::distrobox_downloader::DISTROBOX_VERSION.to_string()
+        } else {
+            pinned.into()
+        }
+    }
+
+    /// Switches to the distrobox executable selected in the settings, downloading the bundled
+    /// one if needed
+    fn apply_distrobox_executable(&self) {
+        if self.settings().string("distrobox-executable") != "bundled" {
+            self.distrobox_version().refetch();
+            self.update_bundled_update_available();
+            return;
+        }
+        let version = self.bundled_distrobox_version();
+        // Other bundled versions only stand in for the default one while it's downloaded
+        let pinned = !self
+            .settings()
+            .string("distrobox-pinned-version")
+            .is_empty();
+        let pinned_missing =
+            pinned && !crate::distrobox_downloader::get_bundled_distrobox_path(&version).exists();
+        // The version and its checksum are applied one at a time, only download once both are valid
+        let pinned_sha256 = self.settings().string("distrobox-pinned-sha256");
+        if pinned_missing
+            && (crate::distrobox_downloader::validate_version(&version).is_err()
+                || crate::distrobox_downloader::expected_checksum(&version, &pinned_sha256)
+                    .is_err())
+        {
+            self.update_bundled_update_available();
+            return;
+        }
+        let resolve = move || crate::distrobox_downloader::resolve_bundled_distrobox_path(&version);
+        if pinned_missing || resolve().is_none() {
+            let task = self.download_distrobox();
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to=this)]
+                self,
+                async move {
+                    task.wait_ended().await;
+                    this.revert_unresolved_bundled_distrobox(resolve(), task.error_message());
+                }
+            ));
+        } else if !self.revert_unresolved_bundled_distrobox(resolve(), None) {
+            self.distrobox_version().refetch();
+        }
+        self.update_bundled_update_available();
+    }
+
     pub fn download_distrobox(&self) -> DistroboxTask {
         // Guard: if a download task is already in progress, return it instead of creating a duplicate
         for task in self.tasks().iter() {
@@ -587,8 +802,24 @@ impl RootStore {
             }
         }
         let root_store_weak = self.downgrade();
+        let offline = self.offline_mode();
+        let version = self.bundled_distrobox_version();
+        let pinned_sha256 = self.settings().string("distrobox-pinned-sha256");
         let task = self.create_task("system", "Downloading Distrobox", move |task| async move {
-            crate::distrobox_downloader::download_distrobox(task, root_store_weak).await
+            if offline {
+                anyhow::bail!("Offline mode is enabled, distrobox can't be downloaded");
+            }
+            // We should be able to actually use a CommandRunner runs in the flatpak sandbox, because it has all the tools we need.
+            // Also, the data folder is writable there and should be mapped to the host.
+            let command_runner = CommandRunner::new_real();
+            crate::distrobox_downloader::download_distrobox(
+                command_runner,
+                task,
+                &version,
+                &pinned_sha256,
+                root_store_weak,
+            )
+            .await
         });
         self.set_selected_task(Some(task.clone()));
         task
@@ -607,6 +838,7 @@ impl RootStore {
                 Ok(stream) => stream,
                 Err(e) => {
                     warn!("Failed to start podman events listener: {}", e);
+                    this.start_polling_containers();
                     return;
                 }
             };
@@ -644,6 +876,36 @@ impl RootStore {
                 .await;
 
             warn!("Podman events listener stopped");
+            this.start_polling_containers();
+        });
+    }
+
+    /// Refreshes the containers list every `containers-poll-interval` seconds, for when
+    /// podman events can't tell us about changes
+    fn start_polling_containers(&self) {
+        if self.polling_containers() {
+            return;
+        }
+        info!("Polling the containers list");
+        self.imp().polling_containers.set(true);
+        self.notify_polling_containers();
+
+        let this = self.downgrade();
+        glib::spawn_future_local(async move {
+            loop {
+                let Some(interval) = this
+                    .upgrade()
+                    .map(|this| this.settings().uint("containers-poll-interval"))
+                else {
+                    break;
+                };
+                glib::timeout_future(Duration::from_secs(interval.into())).await;
+                let Some(this) = this.upgrade() else {
+                    break;
+                };
+                this.containers_query()
+                    .refetch_if_stale(Duration::from_secs(1));
+            }
         });
     }
 
@@ -660,37 +922,194 @@ impl RootStore {
         info!("Creating new distrobox task");
         let name = name.to_string();
         let action = action.to_string();
+        let failure_title = gettext_f!(
+            "Task failed: {action} {name}",
+            "action" => action,
+            "name" => name
+        );
 
         let task = DistroboxTask::new(&name, &action, move |task| async move {
             debug!("Starting task execution");
-            let result = operation(task).await;
+            let command_runner = this.command_runner();
+            task.set_command_window_start(command_runner.output_events_count());
+            let result = operation(task.clone()).await;
+            task.set_command_window_end(command_runner.output_events_count());
             if let Err(ref e) = result {
                 error!(error = %e, "Task execution failed");
+                this.push_notification(NotificationLevel::Error, &failure_title, &e.to_string());
             }
             this.load_containers();
             result
         });
 
         self.tasks().append(&task);
+        task.connect_status_notify(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| this.update_active_tasks()
+        ));
+        self.update_active_tasks();
         task
     }
 
+    /// Applies the timeouts configured for each kind of query, 0 meaning no timeout
+    fn apply_query_timeouts(&self) {
+        let timeout = |key| match self.settings().uint(key) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs.into())),
+        };
+        self.distrobox_version()
+            .set_timeout(timeout("version-timeout"));
+        self.containers_query().set_timeout(timeout("list-timeout"));
+        self.images_query().set_timeout(timeout("images-timeout"));
+        self.downloaded_images_query()
+            .set_timeout(timeout("images-timeout"));
+    }
+
+    /// Marks the containers targeted by a task that hasn't ended yet
+    fn update_active_tasks(&self) {
+        let active: HashSet<String> = self
+            .tasks()
+            .iter()
+            .filter(|task| !task.ended())
+            .map(|task| task.target())
+            .collect();
+        for container in self.containers().iter() {
+            let has_active_task = active.contains(&container.name());
+            if container.has_active_task() != has_active_task {
+                container.set_has_active_task(has_active_task);
+            }
+        }
+    }
+
     pub fn clear_ended_tasks(&self) {
         self.tasks().retain(|task| !task.ended());
     }
 
+    pub fn notifications(&self) -> &TypedListStore<Notification> {
+        &self.imp().notifications
+    }
+
+    pub fn push_notification(&self, level: NotificationLevel, title: &str, message: &str) {
+        let notifications = self.notifications();
+        // A query failing on every refresh shouldn't flood the list
+        if let Some(latest) = notifications.first()
+            && latest.level() == level
+            && latest.title() == title
+            && latest.message() == message
+        {
+            notifications.remove(0);
+        }
+        notifications.insert(0, &Notification::new(level, title, message));
+        while notifications.len() > MAX_NOTIFICATIONS {
+            notifications.remove(notifications.len() - 1);
+        }
+    }
+
+    pub fn clear_notifications(&self) {
+        self.notifications().remove_all();
+    }
+
+    fn notify_query_errors<T: Clone + 'static>(&self, query: &Query<T>, title: &str) {
+        let this = self.clone();
+        let title = title.to_string();
+        query.connect_error(move |error| {
+            this.push_notification(NotificationLevel::Error, &title, &error.to_string());
+        });
+    }
+
     pub fn create_container(&self, create_args: CreateArgs) {
+        let task = self.start_create_container(create_args);
+        self.view_task(&task);
+    }
+    /// Suffixes a derived container name when a container already has it
+    pub fn available_container_name(
+        &self,
+        name: backends::CreateArgName,
+    ) -> backends::CreateArgName {
+        let taken: HashSet<String> = self.containers().iter().map(|c| c.name()).collect();
+        unique_container_name(name, &taken)
+    }
+    /// Creates a container of a known distro with the default settings, skipping the create dialog
+    pub fn quick_create(&self, distro: &str) -> anyhow::Result<()> {
+        let entry = quick_create_entry(distro)
+            .with_context(|| format!("No quick create image for {distro}"))?;
+        let name = derive_container_name(entry.image)
+            .with_context(|| format!("Can't derive a container name from {}", entry.image))?;
+        self.create_container(CreateArgs {
+            name: self.available_container_name(name),
+            image: Some(backends::CreateArgsImage::new(entry.image)?),
+            no_entry: self.settings().boolean("distrobox-create-no-entry"),
+            ..Default::default()
+        });
+        Ok(())
+    }
+    /// Like `create_container`, without showing the task
+    pub fn start_create_container(&self, mut create_args: CreateArgs) -> DistroboxTask {
         let this = self.clone();
         let name = create_args.name.to_string();
-        let task = self.create_task(&name, "create", move |task| async move {
-            task.set_description(
-                "Creation requires downloading the container image, which may take some time...",
-            );
+        let offline = self.offline_mode();
+        if offline {
+            create_args.additional_flags.push("--pull=never".into());
+        }
+        self.create_task(&name, "create", move |task| async move {
+            if offline {
+                // distrobox pulls missing images by itself, which hangs without a network
+                this.ensure_image_available_offline(create_args.image.as_ref())
+                    .await?;
+            } else {
+                task.set_description(
+                    "Creation requires downloading the container image, which may take some time...",
+                );
+            }
             let child = this.distrobox().create(create_args).await?;
             task.handle_child_output(child).await
-        });
-        self.view_task(&task);
+        })
+    }
+    /// Waits for a freshly created container to start and finish its setup
+    pub async fn wait_container_ready(&self, name: &str) -> anyhow::Result<()> {
+        // Entering fails when the setup does, the marker file tells whether that's the reason
+        let started = self.distrobox().start(name).await;
+        if !self.setup_completed(name).await? {
+            anyhow::bail!("The setup of {name} didn't complete");
+        }
+        started?;
+        self.load_containers();
+        Ok(())
+    }
+
+    /// Checks whether distrobox-init finished setting up the container
+    pub async fn setup_completed(&self, name: &str) -> anyhow::Result<bool> {
+        Ok(self.distrobox().setup_completed(name).await?)
     }
+
+    /// Waits for distrobox-init to finish the setup of a container being entered.
+    /// The setup is only reported incomplete when the container is still up without
+    /// the marker at `timeout`: a stopped container shows its failure in the terminal.
+    pub async fn wait_setup_completed(
+        &self,
+        name: &str,
+        timeout: Duration,
+    ) -> anyhow::Result<bool> {
+        let poll_interval = Duration::from_secs(2);
+        let mut waited = Duration::ZERO;
+        loop {
+            if self.setup_completed(name).await? {
+                return Ok(true);
+            }
+            if waited >= timeout {
+                break;
+            }
+            glib::timeout_future(poll_interval).await;
+            waited += poll_interval;
+        }
+        let containers = self.distrobox().list().await?;
+        let is_up = containers
+            .get(name)
+            .is_some_and(|info| matches!(info.status, Status::Up(_)));
+        Ok(!is_up)
+    }
+
     pub fn clone_container(&self, source_name: &str, create_args: CreateArgs) {
         let this = self.clone();
         let name = create_args.name.to_string();
@@ -701,12 +1120,116 @@ impl RootStore {
             let source = source.clone();
             async move {
                 task.set_description("Cloning container (may take some time)...");
-                let child = this.distrobox().clone_from(&source, create_args).await?;
-                task.handle_child_output(child).await
+                // Distrobox manages the containers of the runtime we detected
+                let source_manager = this
+                    .container_runtime()
+                    .data()
+                    .and_then(|runtime| backends::ContainerManager::from_name(runtime.name()));
+                let target_manager = create_args
+                    .container_manager
+                    .filter(|target| source_manager.is_some_and(|source| source != *target));
+                let child = this
+                    .distrobox()
+                    .clone_from(&source, source_manager, create_args)
+                    .await?;
+                task.handle_child_output(child).await?;
+                // The list only shows the containers of the detected runtime
+                if let (Some(source), Some(target)) = (source_manager, target_manager) {
+                    task.set_description(format!(
+                        "The clone is managed by {}, so it isn't listed with the {} containers.",
+                        target.as_str(),
+                        source.as_str()
+                    ));
+                }
+                Ok(())
             }
         });
         self.view_task(&task);
     }
+
+    async fn ensure_image_available_offline(
+        &self,
+        image: Option<&backends::CreateArgsImage>,
+    ) -> anyhow::Result<()> {
+        let Some(image) = image else {
+            return Ok(());
+        };
+        let runtime = self
+            .container_runtime()
+            .data()
+            .context("Container runtime not available")?;
+        if !runtime.image_exists(image.as_str()).await? {
+            anyhow::bail!("Offline mode is enabled and {image} isn't available locally");
+        }
+        Ok(())
+    }
+
+    /// The volumes added by the user to a container, to give them to another one
+    pub async fn container_user_volumes(
+        &self,
+        container: &Container,
+    ) -> anyhow::Result<Vec<backends::Volume>> {
+        let runtime = self
+            .container_runtime()
+            .data()
+            .context("Container runtime not available")?;
+        let mounts = runtime.mounts(&container.name()).await?;
+        let home = glib::home_dir();
+        Ok(backends::user_volumes(&mounts, &home.to_string_lossy()))
+    }
+
+    /// Resolves the image a container was created from to a reference pinned to its digest,
+    /// to recreate the container reproducibly. The tag (like `latest`) can't be used:
+    /// it may point to a newer image pulled since then.
+    pub async fn pinned_image(&self, container: &Container) -> anyhow::Result<String> {
+        let runtime = self
+            .container_runtime()
+            .data()
+            .context("Container runtime not available")?;
+        let image_id = runtime.container_image_id(&container.name()).await?;
+        runtime.image_digest(&image_id).await
+    }
+
+    /// Lists the containers of the runtime which weren't created by distrobox
+    pub async fn list_plain_containers(&self) -> anyhow::Result<Vec<RuntimeContainer>> {
+        let runtime = self
+            .container_runtime()
+            .data()
+            .context("Container runtime not available")?;
+        let containers = runtime.list_all_containers().await?;
+        Ok(containers.into_iter().filter(|c| !c.is_distrobox).collect())
+    }
+
+    /// Brings a plain container under distrobox management.
+    /// Labels can't be added to an existing container, so it gets cloned into a new distrobox.
+    /// Distrobox refuses to clone a running container: `stop_first` stops it beforehand,
+    /// otherwise a running source is reported before any task starts.
+    pub async fn adopt_container(
+        &self,
+        source: &RuntimeContainer,
+        stop_first: bool,
+    ) -> anyhow::Result<()> {
+        let name = backends::CreateArgName::new(&format!("{}-distrobox", source.name))?;
+        if source.is_running() {
+            if !stop_first {
+                anyhow::bail!("{} is running, stop it before adopting it", source.name);
+            }
+            let runtime = self
+                .container_runtime()
+                .data()
+                .context("Container runtime not available")?;
+            runtime.stop_container(&source.name).await?;
+        }
+        self.clone_container(
+            &source.name,
+            CreateArgs {
+                name,
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+
     pub fn assemble_container(&self, file_path: &str) {
         let this = self.clone();
         let file_path_clone = file_path.to_string();
@@ -714,8 +1237,13 @@ impl RootStore {
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or(file_path);
+        let offline = self.offline_mode();
 
         let task = self.create_task(file_name, "assemble", move |task| async move {
+            // distrobox assemble fetches remote manifests and pulls the images it misses
+            if offline {
+                anyhow::bail!("Offline mode is enabled, containers can't be assembled");
+            }
             let child = this.distrobox().assemble(&file_path_clone)?;
             task.handle_child_output(child).await
         });
@@ -726,17 +1254,109 @@ impl RootStore {
         let this = self.clone();
         let name_for_task = container.name();
         let name = name_for_task.clone();
+        let offline = self.offline_mode();
         self.create_task(&name_for_task, "upgrade", move |task| async move {
+            if offline {
+                anyhow::bail!("Offline mode is enabled, the container can't be upgraded");
+            }
             let child = this.distrobox().upgrade(&name)?;
-            task.handle_child_output(child).await
+            let mut output = Vec::new();
+            task.handle_child_output_with(child, |chunk| output.extend_from_slice(chunk))
+                .await?;
+            let changes = backends::PackageChanges::parse(&String::from_utf8_lossy(&output));
+            task.set_description(changes.to_string());
+            Ok(())
         })
     }
 
     pub fn launch_app(&self, container: &Container, app: ExportableApp) {
+        self.launch_app_with_env(container, app, vec![]);
+    }
+
+    pub fn launch_history(&self) -> LaunchHistory {
+        LaunchHistory::from_map(
+            self.settings()
+                .value("launch-history")
+                .get()
+                .unwrap_or_default(),
+        )
+    }
+
+    fn record_launch(&self, container: &Container, app: &ExportableApp) {
+        let mut history = self.launch_history();
+        history.record(
+            &container.name(),
+            RecentApp {
+                desktop_file_path: app.desktop_file_path.clone(),
+                name: app.entry.name.clone(),
+            },
+        );
+        self.save_launch_history(&history);
+    }
+
+    fn forget_launches(&self, container_name: &str) {
+        let mut history = self.launch_history();
+        history.forget(container_name);
+        self.save_launch_history(&history);
+    }
+
+    fn save_launch_history(&self, history: &LaunchHistory) {
+        if let Err(e) = self
+            .settings()
+            .set_value("launch-history", &history.to_map().to_variant())
+        {
+            error!(error = %e, "Failed to save the launch history");
+        }
+    }
+
+    /// Launches an app of the launch history, looking up its desktop entry first when the apps
+    /// of the container aren't loaded
+    pub fn launch_recent_app(&self, container: &Container, desktop_file_path: &str) {
+        let cached = container.apps().data().and_then(|apps| {
+            apps.iter()
+                .map(|obj| obj.borrow::<ExportableApp>().clone())
+                .find(|app| app.desktop_file_path == desktop_file_path)
+        });
+        if let Some(app) = cached {
+            self.launch_app(container, app);
+            return;
+        }
+        let this = self.clone();
+        let container = container.clone();
+        let desktop_file_path = desktop_file_path.to_string();
+        self.create_task(&container.name(), "find-app", move |_task| async move {
+            let app = this
+                .distrobox()
+                .list_apps(&container.name())
+                .await?
+                .into_iter()
+                .find(|app| app.desktop_file_path == desktop_file_path)
+                .with_context(|| format!("{desktop_file_path} is no longer in the container"))?;
+            this.launch_app(&container, app);
+            Ok(())
+        });
+    }
+
+    /// Launches an app with extra environment variables, e.g. `GTK_DEBUG=interactive`
+    pub fn launch_app_with_env(
+        &self,
+        container: &Container,
+        app: ExportableApp,
+        env: Vec<(String, String)>,
+    ) {
         let this = self.clone();
         let container = container.clone();
+        let session = if self.settings().boolean("launch-apps-dedicated-session") {
+            LaunchSession::Dedicated
+        } else {
+            LaunchSession::Shared
+        };
         self.create_task(&container.name(), "launch-app", move |task| async move {
-            let child = this.distrobox().launch_app(&container.name(), &app)?;
+            let child =
+                this.distrobox()
+                    .launch_app_with_env(&container.name(), &app, &session, &env)?;
+            // Only apps which could be started are worth relaunching
+            this.record_launch(&container, &app);
             task.handle_child_output(child).await
         });
     }
@@ -803,6 +1423,108 @@ impl RootStore {
             this.distrobox()
                 .export_app(&container.name(), &desktop_file_path)
                 .await?;
+            this.apply_launch_session_to_export(&container.name(), &desktop_file_path)
+                .await?;
+            container.apps().refetch();
+            Ok(())
+        });
+    }
+
+    /// Makes an app just exported from `box_name` follow the launch session preference
+    async fn apply_launch_session_to_export(
+        &self,
+        box_name: &str,
+        desktop_file_path: &str,
+    ) -> anyhow::Result<()> {
+        if !self.settings().boolean("launch-apps-dedicated-session") {
+            return Ok(());
+        }
+        let file_name = Path::new(desktop_file_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .context("Invalid desktop file path")?;
+        if !self
+            .distrobox()
+            .export_dedicated_session(box_name, file_name)
+            .await?
+        {
+            warn!(container = %box_name, "No dbus-run-session in the container, the exported app shares its session");
+        }
+        Ok(())
+    }
+
+    /// Exports several apps in a single task
+    pub fn export_apps(&self, container: &Container, desktop_file_paths: Vec<String>) {
+        let container = container.clone();
+        self.export_apps_from(&container.name(), desktop_file_paths)
+            .connect_status_notify(move |task| {
+                if task.is_successful() {
+                    container.apps().refetch();
+                }
+            });
+    }
+
+    /// Exports the apps of the group that aren't exported yet. Returns None when there are none
+    pub fn export_app_group(
+        &self,
+        container: &Container,
+        group: &backends::AppGroup,
+    ) -> Option<DistroboxTask> {
+        let paths = group.unexported_paths();
+        if paths.is_empty() {
+            return None;
+        }
+        let container = container.clone();
+        let task = self.export_apps_from(&container.name(), paths);
+        task.connect_status_notify(move |task| {
+            if task.is_successful() {
+                container.apps().refetch();
+            }
+        });
+        Some(task)
+    }
+
+    /// Exports several apps of the container called `name` in a single task
+    pub fn export_apps_from(&self, name: &str, desktop_file_paths: Vec<String>) -> DistroboxTask {
+        let this = self.clone();
+        let box_name = name.to_string();
+        self.create_task(name, "export-apps", move |task| async move {
+            for desktop_file_path in &desktop_file_paths {
+                task.set_description(format!("Exporting {desktop_file_path}"));
+                this.distrobox()
+                    .export_app(&box_name, desktop_file_path)
+                    .await?;
+                this.apply_launch_session_to_export(&box_name, desktop_file_path)
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Exports an app, showing it in the app menu under `display_name` instead of its own name
+    pub fn export_app_as(
+        &self,
+        container: &Container,
+        desktop_file_path: &str,
+        display_name: &str,
+    ) {
+        let this = self.clone();
+        let container = container.clone();
+        let desktop_file_path = desktop_file_path.to_string();
+        let display_name = display_name.to_string();
+        self.create_task(&container.name(), "export", move |_task| async move {
+            this.distrobox()
+                .export_app(&container.name(), &desktop_file_path)
+                .await?;
+            this.apply_launch_session_to_export(&container.name(), &desktop_file_path)
+                .await?;
+            let file_name = Path::new(&desktop_file_path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .context("Invalid desktop file path")?;
+            this.distrobox()
+                .rename_exported_app(&container.name(), file_name, &display_name)
+                .await?;
             container.apps().refetch();
             Ok(())
         });
@@ -821,6 +1543,24 @@ impl RootStore {
         });
     }
 
+    /// Removes exported desktop files whose app was uninstalled from the container.
+    pub fn remove_stale_exports(&self, container: &Container, exports: Vec<StaleExport>) {
+        let this = self.clone();
+        let container = container.clone();
+        self.create_task(
+            &container.name(),
+            "cleanup-exports",
+            move |_task| async move {
+                for export in &exports {
+                    this.distrobox().remove_stale_export(export).await?;
+                }
+                container.stale_exports().refetch();
+                container.apps().refetch();
+                Ok(())
+            },
+        );
+    }
+
     pub fn export_binary(&self, container: &Container, binary_path: &str) -> DistroboxTask {
         let this = self.clone();
         let container = container.clone();
@@ -855,14 +1595,59 @@ impl RootStore {
         );
     }
 
-    pub fn delete_container(&self, container: &Container) {
+    fn update_protected_containers(&self) {
+        let protected = self.settings().strv("protected-containers");
+        for container in self.containers().iter() {
+            let is_protected = protected.iter().any(|name| *name == container.name());
+            if container.protected() != is_protected {
+                container.set_protected(is_protected);
+            }
+        }
+    }
+
+    pub fn set_container_protected(&self, container: &Container, protected: bool) {
+        let name = container.name();
+        let mut names: Vec<String> = self
+            .settings()
+            .strv("protected-containers")
+            .iter()
+            .map(|n| n.to_string())
+            .filter(|n| *n != name)
+            .collect();
+        if protected {
+            names.push(name);
+        }
+        if let Err(e) = self.settings().set_strv("protected-containers", names) {
+            error!(error = %e, "Failed to save the protected containers");
+        }
+        container.set_protected(protected);
+    }
+
+    /// Removes the container. When `graceful` is set, the container is stopped first,
+    /// instead of being killed by the forced removal.
+    /// Protected containers are only removed when `confirmation` is their name.
+    pub fn delete_container(
+        &self,
+        container: &Container,
+        graceful: bool,
+        confirmation: Option<&str>,
+    ) -> Result<DistroboxTask, ProtectedContainerError> {
+        if container.protected() && confirmation != Some(container.name().as_str()) {
+            return Err(ProtectedContainerError(container.name()));
+        }
         let name_for_task = container.name();
         let name = name_for_task.clone();
         let this = self.clone();
-        self.create_task(&name_for_task, "delete", move |_task| async move {
+        let task = self.create_task(&name_for_task, "delete", move |task| async move {
+            if graceful {
+                task.set_description(format!("Stopping {name}"));
+                this.distrobox().stop(&name).await?;
+            }
             this.distrobox().remove(&name).await?;
+            this.forget_launches(&name);
             Ok(())
         });
+        Ok(task)
     }
 
     pub fn stop_container(&self, container: &Container) {
@@ -875,16 +1660,102 @@ impl RootStore {
         });
     }
 
+    /// Copies the timezone of the host into the container
+    pub fn sync_timezone(&self, container: &Container) -> DistroboxTask {
+        let name_for_task = container.name();
+        let name = name_for_task.clone();
+        let this = self.clone();
+        self.create_task(&name_for_task, "sync-timezone", move |task| async move {
+            let timezone = this.distrobox().host_timezone().await?;
+            task.set_description(format!("Setting the timezone to {timezone}"));
+            this.distrobox().set_timezone(&name, &timezone).await?;
+            Ok(())
+        })
+    }
+
+    /// Saves the desktop files exported by every container into a gzipped tarball
+    pub fn backup_exports(&self, archive: PathBuf) -> DistroboxTask {
+        let this = self.clone();
+        self.create_task("system", "backup-exports", move |task| async move {
+            let count = this.distrobox().backup_exports(&archive).await?;
+            task.set_description(format!("Saved {count} exported apps"));
+            Ok(())
+        })
+    }
+
+    /// Restores the desktop files saved by `backup_exports`
+    pub fn restore_exports(&self, archive: PathBuf) -> DistroboxTask {
+        let this = self.clone();
+        self.create_task("system", "restore-exports", move |_task| async move {
+            this.distrobox().restore_exports(&archive).await?;
+            for container in this.containers().iter() {
+                if container.apps().data().is_some() {
+                    container.apps().refetch();
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Restarts the container to run its setup again, e.g. after a failed init hook
+    pub fn rerun_setup(&self, container: &Container) -> DistroboxTask {
+        let name_for_task = container.name();
+        let name = name_for_task.clone();
+        let this = self.clone();
+        self.create_task(&name_for_task, "rerun-setup", move |_task| async move {
+            let restarted = this.distrobox().rerun_setup(&name).await;
+            if !this.setup_completed(&name).await? {
+                anyhow::bail!("The container setup didn't complete, check the container logs");
+            }
+            restarted?;
+            this.load_containers();
+            Ok(())
+        })
+    }
+
     pub fn spawn_container_terminal(&self, container: &Container) -> DistroboxTask {
         let name_for_task = container.name();
         let name = name_for_task.clone();
         let this = self.clone();
+        let default_command = self.container_default_command(&name_for_task);
         self.create_task(&name_for_task, "spawn-terminal", move |_task| async move {
-            let enter_cmd = this.distrobox().enter_cmd(&name);
+            let enter_cmd = this
+                .distrobox()
+                .enter_cmd_with_command(&name, default_command.as_deref());
             this.spawn_terminal_cmd(name, &enter_cmd).await
         })
     }
 
+    /// The command run when opening a terminal in the container, if any
+    pub fn container_default_command(&self, name: &str) -> Option<String> {
+        let commands: HashMap<String, String> = self
+            .settings()
+            .value("container-default-commands")
+            .get()
+            .unwrap_or_default();
+        default_command_for(&commands, name).map(|command| command.to_string())
+    }
+
+    /// Stores the command run when opening a terminal in the container. An empty one means a plain shell.
+    pub fn set_container_default_command(&self, name: &str, command: &str) {
+        let mut commands: HashMap<String, String> = self
+            .settings()
+            .value("container-default-commands")
+            .get()
+            .unwrap_or_default();
+        if command.trim().is_empty() {
+            commands.remove(name);
+        } else {
+            commands.insert(name.to_string(), command.trim().to_string());
+        }
+        if let Err(e) = self
+            .settings()
+            .set_value("container-default-commands", &commands.to_variant())
+        {
+            error!(error = %e, "Failed to save the default command of {name}");
+        }
+    }
+
     pub fn upgrade_all(&self) {
         for container in self.containers().iter() {
             self.upgrade_container(&container);
@@ -989,13 +1860,40 @@ impl RootStore {
             }
         };
         info!(terminal = %terminal.program, "Validating terminal");
+        self.check_terminal(&terminal, Duration::from_secs(5)).await
+    }
+
+    /// Runs a command through the terminal, which writes a marker file that is then waited for.
+    /// Terminals returning right away (like gnome-terminal) run the command asynchronously,
+    /// so the marker is polled until `timeout`.
+    async fn check_terminal(
+        &self,
+        terminal: &Terminal,
+        timeout: Duration,
+    ) -> Result<(), anyhow::Error> {
+        let marker = terminal_check_marker_path();
+        let marker = marker.to_string_lossy();
+        let _ = self
+            .command_runner()
+            .output(Command::new_with_args("rm", ["-f", &*marker]))
+            .await;
 
-        // Try running a simple command to validate the terminal
         let mut cmd = Command::new(terminal.program.clone());
         cmd.args(terminal.extra_args.clone())
-            .arg(terminal.separator_arg)
-            .arg("echo")
-            .arg("DistroShelf terminal validation");
+            .arg(&terminal.separator_arg)
+            .args([
+                "sh",
+                "-c",
+                r#"echo "$1" > "$2""#,
+                "sh",
+                TERMINAL_CHECK_TEXT,
+                &*marker,
+            ]);
+
+        let separator_hint = format!(
+            "Check its separator argument '{}': terminals expect the command after an argument like '-e', '--' or '-x'.",
+            terminal.separator_arg
+        );
 
         let mut child = match self.command_runner().spawn(cmd) {
             Ok(child) => child,
@@ -1012,12 +1910,36 @@ impl RootStore {
         if !child.wait().await?.success() {
             error!(terminal = %terminal.program, "Terminal validation failed");
             return Err(anyhow::anyhow!(
-                "Terminal validation failed. '{}' did not run successfully.",
-                &terminal.program
+                "Terminal validation failed. '{}' did not run successfully. {}",
+                &terminal.program,
+                separator_hint
             ));
         }
 
-        Ok(())
+        let poll_interval = Duration::from_millis(250);
+        let mut waited = Duration::ZERO;
+        loop {
+            let content = self
+                .command_runner()
+                .output_string(Command::new_with_args("cat", [&*marker]))
+                .await
+                .unwrap_or_default();
+            if content.trim() == TERMINAL_CHECK_TEXT {
+                return Ok(());
+            }
+            if waited >= timeout {
+                break;
+            }
+            glib::timeout_future(poll_interval).await;
+            waited += poll_interval;
+        }
+
+        error!(terminal = %terminal.program, separator = %terminal.separator_arg, "Terminal didn't run the validation command");
+        Err(anyhow::anyhow!(
+            "'{}' started, but didn't run the command. {}",
+            &terminal.program,
+            separator_hint
+        ))
     }
     fn reload_till_up(&self, name: String, times: usize) {
         let this = self.clone();
@@ -1149,25 +2071,7 @@ mod tests {
 
     use super::*;
     use crate::fakers::NullCommandRunnerBuilder;
-
-    fn spin_main_context_until(timeout: Duration, mut condition: impl FnMut() -> bool) {
-        let context = glib::MainContext::ref_thread_default();
-        let deadline = Instant::now() + timeout;
-
-        while Instant::now() < deadline {
-            while context.pending() {
-                context.iteration(false);
-            }
-            if condition() {
-                return;
-            }
-            std::thread::sleep(Duration::from_millis(1));
-        }
-
-        while context.pending() {
-            context.iteration(false);
-        }
-    }
+    use crate::test_utils::{container, spin_main_context_until};
 
     #[gtk::test]
     fn test_resolve_path() {
@@ -1479,6 +2383,640 @@ mod tests {
         assert_eq!(store.tasks().iter().count(), 1);
     }
 
+    #[gtk::test]
+    fn test_refresh_all_refetches_every_query() {
+        let store = RootStore::new(NullCommandRunnerBuilder::new().build());
+        let queries_started = Rc::new(RefCell::new(Vec::new()));
+
+        let started = queries_started.clone();
+        store.containers_query().set_fetcher(move || {
+            started.borrow_mut().push("containers");
+            async { pending::<anyhow::Result<Vec<Container>>>().await }
+        });
+        let started = queries_started.clone();
+        store.images_query().set_fetcher(move || {
+            started.borrow_mut().push("images");
+            async { pending::<anyhow::Result<Vec<String>>>().await }
+        });
+        let started = queries_started.clone();
+        store.downloaded_images_query().set_fetcher(move || {
+            started.borrow_mut().push("downloaded_images");
+            async { pending::<anyhow::Result<HashSet<String>>>().await }
+        });
+        let started = queries_started.clone();
+        store.container_runtime().set_fetcher(move || {
+            started.borrow_mut().push("container_runtime");
+            async { pending::<anyhow::Result<Rc<dyn ContainerRuntime>>>().await }
+        });
+        let started = queries_started.clone();
+        store.distrobox_version().set_fetcher(move || {
+            started.borrow_mut().push("distrobox_version");
+            async { pending::<anyhow::Result<String>>().await }
+        });
+
+        assert!(!store.refreshing());
+        store.refresh_all();
+        // fetchers are started from the main context
+        spin_main_context_until(Duration::from_millis(200), || {
+            queries_started.borrow().len() == 5
+        });
+
+        let mut started = queries_started.borrow().clone();
+        started.sort_unstable();
+        assert_eq!(
+            started,
+            [
+                "container_runtime",
+                "containers",
+                "distrobox_version",
+                "downloaded_images",
+                "images"
+            ]
+        );
+        assert!(store.refreshing());
+    }
+
+    #[gtk::test]
+    fn test_query_error_pushes_notification() {
+        let store = RootStore::new(NullCommandRunnerBuilder::new().build());
+        store
+            .images_query()
+            .set_fetcher(|| async { anyhow::bail!("registry unreachable") });
+
+        store.images_query().refetch();
+        spin_main_context_until(Duration::from_millis(200), || {
+            !store.notifications().is_empty()
+        });
+
+        let notification = store.notifications().first().unwrap();
+        assert_eq!(notification.level(), NotificationLevel::Error);
+        assert_eq!(notification.title(), "Failed to load images");
+        assert_eq!(notification.message(), "registry unreachable");
+        assert!(notification.created_at() > 0);
+
+        // The same failure again only refreshes the existing entry
+        store.images_query().refetch();
+        spin_main_context_until(Duration::from_millis(200), || {
+            !store.images_query().is_loading()
+        });
+        assert_eq!(store.notifications().len(), 1);
+    }
+
+    #[gtk::test]
+    fn test_task_records_its_commands() {
+        let runner = NullCommandRunnerBuilder::new().build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let store = RootStore::new(runner.clone());
+
+        let _ = smol::block_on(runner.output(Command::new_with_args("before", ["task"])));
+
+        let task_runner = runner.clone();
+        let task = store.create_task("ubuntu", "test", move |_task| async move {
+            task_runner
+                .output(Command::new_with_args("distrobox", ["upgrade", "ubuntu"]))
+                .await?;
+            Ok(())
+        });
+        spin_main_context_until(Duration::from_millis(200), || task.ended());
+        assert!(task.is_successful());
+
+        let _ = smol::block_on(runner.output(Command::new_with_args("after", ["task"])));
+
+        let commands: Vec<String> = task
+            .commands(&tracker.items())
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(commands, ["distrobox upgrade ubuntu"]);
+    }
+
+    #[gtk::test]
+    fn test_container_has_active_task_while_task_runs() {
+        let store = RootStore::new(NullCommandRunnerBuilder::new().build());
+        let container = container(
+            store.distrobox(),
+            store.container_runtime(),
+            "ubuntu",
+            Status::Up("2 minutes ago".into()),
+            "docker.io/library/ubuntu:latest",
+        );
+        store.containers().append(&container);
+
+        let (finish, finished) = futures::channel::oneshot::channel::<()>();
+        let task = store.create_task("ubuntu", "test", move |_task| async move {
+            let _ = finished.await;
+            Ok(())
+        });
+        spin_main_context_until(Duration::from_millis(50), || false);
+        assert!(container.has_active_task());
+
+        finish.send(()).unwrap();
+        spin_main_context_until(Duration::from_millis(200), || task.ended());
+        assert!(task.is_successful());
+        assert!(!container.has_active_task());
+    }
+
+    #[test]
+    fn test_default_command_appended_to_enter() {
+        let distrobox = Distrobox::new(
+            NullCommandRunnerBuilder::new().build(),
+            crate::backends::distrobox::command::default_cmd_factory(),
+        );
+        let commands = HashMap::from([
+            ("ubuntu".to_string(), "tmux new -A -s main".to_string()),
+            ("fedora".to_string(), "  ".to_string()),
+        ]);
+        let enter = |name: &str| {
+            distrobox
+                .enter_cmd_with_command(name, default_command_for(&commands, name))
+                .to_string()
+        };
+
+        assert_eq!(
+            enter("ubuntu"),
+            "distrobox enter ubuntu --no-workdir -- sh -c tmux new -A -s main"
+        );
+        assert_eq!(enter("fedora"), "distrobox enter fedora --no-workdir");
+        assert_eq!(enter("arch"), "distrobox enter arch --no-workdir");
+    }
+
+    #[gtk::test]
+    fn test_graceful_delete_stops_before_removing() {
+        let runner = NullCommandRunnerBuilder::new().build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let store = RootStore::new(runner);
+        let container = container(
+            store.distrobox(),
+            store.container_runtime(),
+            "ubuntu",
+            Status::Up("2 minutes ago".into()),
+            "docker.io/library/ubuntu:latest",
+        );
+
+        let task = store.delete_container(&container, true, None).unwrap();
+        spin_main_context_until(Duration::from_millis(200), || task.ended());
+        assert!(task.is_successful());
+
+        let commands: Vec<String> = task
+            .commands(&tracker.items())
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(
+            commands,
+            ["distrobox stop --yes ubuntu", "distrobox rm --force ubuntu"]
+        );
+    }
+
+    #[gtk::test]
+    fn test_export_app_group_exports_unexported_apps_of_the_group() {
+        let runner = NullCommandRunnerBuilder::new().build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let store = RootStore::new(runner);
+        let container = container(
+            store.distrobox(),
+            store.container_runtime(),
+            "ubuntu",
+            Status::Up("2 minutes ago".into()),
+            "docker.io/library/ubuntu:latest",
+        );
+        let app = |name: &str, category: &str, exported: bool| ExportableApp {
+            entry: backends::desktop_file::DesktopEntry {
+                name: name.into(),
+                exec: name.into(),
+                categories: vec![category.into()],
+                ..Default::default()
+            },
+            desktop_file_path: format!("/usr/share/applications/{name}.desktop"),
+            exported,
+        };
+        let groups = backends::group_apps_by_category([
+            app("code", "Development", false),
+            app("gimp", "Graphics", false),
+            app("builder", "Development", true),
+            app("meld", "Development", false),
+        ]);
+        let development = groups
+            .iter()
+            .find(|group| group.category.as_deref() == Some("Development"))
+            .unwrap();
+
+        let task = store.export_app_group(&container, development).unwrap();
+        spin_main_context_until(Duration::from_millis(200), || task.ended());
+        assert!(task.is_successful());
+
+        let commands: Vec<String> = task
+            .commands(&tracker.items())
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                "distrobox enter --name ubuntu -- distrobox-export --app /usr/share/applications/code.desktop",
+                "distrobox enter --name ubuntu -- distrobox-export --app /usr/share/applications/meld.desktop",
+            ]
+        );
+
+        let graphics = groups.last().unwrap();
+        let exported = backends::AppGroup {
+            apps: graphics
+                .apps
+                .iter()
+                .cloned()
+                .map(|app| ExportableApp {
+                    exported: true,
+                    ..app
+                })
+                .collect(),
+            ..graphics.clone()
+        };
+        assert!(store.export_app_group(&container, &exported).is_none());
+    }
+
+    #[gtk::test]
+    fn test_protected_delete_requires_confirmation() {
+        let runner = NullCommandRunnerBuilder::new().build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let store = RootStore::new(runner);
+        let container = container(
+            store.distrobox(),
+            store.container_runtime(),
+            "important",
+            Status::Exited("1 hour ago".into()),
+            "docker.io/library/ubuntu:latest",
+        );
+        container.set_protected(true);
+
+        assert!(store.delete_container(&container, false, None).is_err());
+        assert!(
+            store
+                .delete_container(&container, false, Some("importan"))
+                .is_err()
+        );
+        spin_main_context_until(Duration::from_millis(50), || false);
+        assert!(tracker.items().is_empty());
+
+        let task = store
+            .delete_container(&container, false, Some("important"))
+            .unwrap();
+        spin_main_context_until(Duration::from_millis(200), || task.ended());
+        let commands: Vec<String> = task
+            .commands(&tracker.items())
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(commands, ["distrobox rm --force important"]);
+    }
+
+    #[gtk::test]
+    fn test_missing_bundled_distrobox_reverts_to_host() {
+        let store = RootStore::new(NullCommandRunnerBuilder::new().build());
+        // Keeps the settings handler from downloading for real
+        store.set_offline_mode(true);
+        store
+            .settings()
+            .set_string("distrobox-executable", "host")
+            .unwrap();
+        let missing = PathBuf::from("/nonexistent/distroshelf/distrobox");
+        assert!(!store.revert_unresolved_bundled_distrobox(Some(missing.clone()), None));
+
+        store
+            .settings()
+            .set_string("distrobox-executable", "bundled")
+            .unwrap();
+        assert!(store.revert_unresolved_bundled_distrobox(Some(missing), None));
+        assert_eq!(store.settings().string("distrobox-executable"), "host");
+        let notification = store.notifications().first().unwrap();
+        assert_eq!(notification.level(), NotificationLevel::Error);
+        assert!(
+            notification
+                .message()
+                .contains("/nonexistent/distroshelf/distrobox is missing or not executable")
+        );
+    }
+
+    #[gtk::test]
+    fn test_events_failure_switches_to_polling() {
+        let runner = NullCommandRunnerBuilder::new()
+            .cmd_full(
+                Command::new_with_args("podman", ["events", "--format", "json"]),
+                || Err(io::Error::other("podman not found")),
+            )
+            .build();
+        let store = RootStore::new(runner);
+        assert!(!store.polling_containers());
+
+        store.start_listening_podman_events();
+        spin_main_context_until(Duration::from_millis(200), || store.polling_containers());
+        assert!(store.polling_containers());
+    }
+
+    #[gtk::test]
+    fn test_offline_mode_skips_downloads() {
+        let runner = NullCommandRunnerBuilder::new()
+            .cmd_full(
+                Command::new_with_args("docker", ["image", "inspect", "alpine:latest"]),
+                || Err(io::Error::other("image not known")),
+            )
+            // The runtime reports a missing image with a failing exit status
+            .cmd_exit_code(
+                Command::new_with_args("docker", ["image", "inspect", "fedora:latest"]),
+                1,
+            )
+            .build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let store = RootStore::new(runner.clone());
+        let docker = crate::backends::docker::Docker::new(Rc::new(runner));
+        let docker: Rc<dyn ContainerRuntime> = Rc::new(docker);
+        store
+            .container_runtime()
+            .set_fetcher(move || std::future::ready(Ok(docker.clone())));
+        store.container_runtime().refetch();
+        spin_main_context_until(Duration::from_millis(200), || {
+            store.container_runtime().data().is_some()
+        });
+        store.set_offline_mode(true);
+
+        let download = store.download_distrobox();
+        spin_main_context_until(Duration::from_millis(200), || download.ended());
+        assert!(download.is_failed());
+        assert!(download.commands(&tracker.items()).is_empty());
+
+        store.create_container(CreateArgs {
+            name: backends::CreateArgName::new("alpine").unwrap(),
+            image: Some(backends::CreateArgsImage::new("alpine:latest").unwrap()),
+            ..Default::default()
+        });
+        let create = store.tasks().last().unwrap();
+        spin_main_context_until(Duration::from_millis(200), || create.ended());
+        assert!(create.is_failed());
+        let commands: Vec<String> = create
+            .commands(&tracker.items())
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        // The missing image is detected before running distrobox
+        assert_eq!(commands, ["docker image inspect alpine:latest"]);
+
+        store.create_container(CreateArgs {
+            name: backends::CreateArgName::new("fedora").unwrap(),
+            image: Some(backends::CreateArgsImage::new("fedora:latest").unwrap()),
+            ..Default::default()
+        });
+        let create = store.tasks().last().unwrap();
+        spin_main_context_until(Duration::from_millis(200), || create.ended());
+        assert!(create.is_failed());
+        assert_eq!(
+            create.error_message().as_deref(),
+            Some("Offline mode is enabled and fedora:latest isn't available locally")
+        );
+        let commands: Vec<String> = create
+            .commands(&tracker.items())
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(commands, ["docker image inspect fedora:latest"]);
+
+        // Assembling and upgrading may download too
+        store.assemble_container("/home/me/boxes.ini");
+        let assemble = store.tasks().last().unwrap();
+        spin_main_context_until(Duration::from_millis(200), || assemble.ended());
+        assert!(assemble.is_failed());
+        assert!(assemble.commands(&tracker.items()).is_empty());
+
+        let container = container(
+            store.distrobox(),
+            store.container_runtime(),
+            "ubuntu",
+            Status::Up("2 minutes ago".into()),
+            "docker.io/library/ubuntu:latest",
+        );
+        let upgrade = store.upgrade_container(&container);
+        spin_main_context_until(Duration::from_millis(200), || upgrade.ended());
+        assert!(upgrade.is_failed());
+        assert!(upgrade.commands(&tracker.items()).is_empty());
+    }
+
+    #[gtk::test]
+    fn test_pinned_image_uses_the_image_of_the_container() {
+        let runner = NullCommandRunnerBuilder::new()
+            .cmd(
+                &["docker", "inspect", "--type", "container", "ubuntu"],
+                r#"[{"Image": "sha256:8a3cdc4d1ad3"}]"#,
+            )
+            .cmd(
+                &["docker", "image", "inspect", "sha256:8a3cdc4d1ad3"],
+                r#"[{"RepoDigests": ["docker.io/library/ubuntu@sha256:2e863c44b718"]}]"#,
+            )
+            // The tag was pulled again since the container was created
+            .cmd(
+                &["docker", "image", "inspect", "ubuntu:latest"],
+                r#"[{"RepoDigests": ["docker.io/library/ubuntu@sha256:6015f66923d7"]}]"#,
+            )
+            .build();
+        let store = RootStore::new(runner.clone());
+        let docker = crate::backends::docker::Docker::new(Rc::new(runner));
+        let docker: Rc<dyn ContainerRuntime> = Rc::new(docker);
+        store
+            .container_runtime()
+            .set_fetcher(move || std::future::ready(Ok(docker.clone())));
+        store.container_runtime().refetch();
+        spin_main_context_until(Duration::from_millis(200), || {
+            store.container_runtime().data().is_some()
+        });
+        let container = container(
+            store.distrobox(),
+            store.container_runtime(),
+            "ubuntu",
+            Status::Up("2 minutes ago".into()),
+            "ubuntu:latest",
+        );
+
+        let pinned = smol::block_on(store.pinned_image(&container)).unwrap();
+        assert_eq!(pinned, "docker.io/library/ubuntu@sha256:2e863c44b718");
+    }
+
+    #[gtk::test]
+    fn test_wait_container_ready_reports_incomplete_setup() {
+        let runner = NullCommandRunnerBuilder::new()
+            // distrobox-init failed, so entering fails too
+            .cmd_exit_code(
+                Command::new_with_args("distrobox", ["enter", "--name", "fedora", "--", "true"]),
+                1,
+            )
+            .cmd(
+                &["sh", "-c", backends::SETUP_DONE_CHECK, "sh", "fedora"],
+                "missing\n",
+            )
+            .cmd(
+                &["sh", "-c", backends::SETUP_DONE_CHECK, "sh", "ubuntu"],
+                "done\n",
+            )
+            .build();
+        let store = RootStore::new(runner);
+
+        let error = smol::block_on(store.wait_container_ready("fedora")).unwrap_err();
+        assert_eq!(error.to_string(), "The setup of fedora didn't complete");
+        assert!(!smol::block_on(store.setup_completed("fedora")).unwrap());
+        assert!(smol::block_on(store.wait_container_ready("ubuntu")).is_ok());
+    }
+
+    #[gtk::test]
+    fn test_wait_setup_completed_only_reports_running_containers() {
+        let runner = NullCommandRunnerBuilder::new()
+            .cmd(
+                &["sh", "-c", backends::SETUP_DONE_CHECK, "sh", "fedora"],
+                "missing\n",
+            )
+            .cmd(
+                &["sh", "-c", backends::SETUP_DONE_CHECK, "sh", "arch"],
+                "missing\n",
+            )
+            .cmd(
+                &["distrobox", "ls", "--no-color"],
+                "ID           | NAME                 | STATUS             | IMAGE
+a0c4e3b2f1d9 | fedora               | Up 2 minutes       | registry.fedoraproject.org/fedora-toolbox:latest
+d24405b14180 | arch                 | Exited (1) 1 minute ago | quay.io/toolbx/arch-toolbox:latest",
+            )
+            .build();
+        let store = RootStore::new(runner);
+
+        // Still setting up when the time is over
+        assert!(!smol::block_on(store.wait_setup_completed("fedora", Duration::ZERO)).unwrap());
+        // The init failed and stopped the container, which the terminal already shows
+        assert!(smol::block_on(store.wait_setup_completed("arch", Duration::ZERO)).unwrap());
+    }
+
+    #[gtk::test]
+    fn test_adopt_running_container_stops_it_first() {
+        let runner = NullCommandRunnerBuilder::new().build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let store = RootStore::new(runner.clone());
+        let docker = crate::backends::docker::Docker::new(Rc::new(runner));
+        let docker: Rc<dyn ContainerRuntime> = Rc::new(docker);
+        store
+            .container_runtime()
+            .set_fetcher(move || std::future::ready(Ok(docker.clone())));
+        store.container_runtime().refetch();
+        spin_main_context_until(Duration::from_millis(200), || {
+            store.container_runtime().data().is_some()
+        });
+        let source = RuntimeContainer {
+            id: "1".into(),
+            name: "web".into(),
+            image: "nginx:latest".into(),
+            state: "running".into(),
+            is_distrobox: false,
+        };
+
+        let error = smol::block_on(store.adopt_container(&source, false)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "web is running, stop it before adopting it"
+        );
+        assert!(store.tasks().is_empty());
+
+        smol::block_on(store.adopt_container(&source, true)).unwrap();
+        let commands: Vec<String> = tracker
+            .items()
+            .iter()
+            .filter_map(|event| event.command())
+            .map(|cmd| cmd.to_string())
+            .collect();
+        assert_eq!(
+            commands.first().map(String::as_str),
+            Some("docker stop web")
+        );
+        assert_eq!(store.tasks().len(), 1);
+    }
+
+    #[gtk::test]
+    fn test_quick_create_issues_create() {
+        let runner = NullCommandRunnerBuilder::new().build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let store = RootStore::new(runner);
+
+        store.quick_create("ubuntu").unwrap();
+        let task = store.tasks().last().unwrap();
+        spin_main_context_until(Duration::from_millis(200), || task.ended());
+        let commands: Vec<String> = task
+            .commands(&tracker.items())
+            .iter()
+            .map(|cmd| cmd.to_string())
+            .collect();
+        let create = commands
+            .iter()
+            .find(|cmd| cmd.starts_with("distrobox create"))
+            .expect("quick create should run distrobox create");
+        assert!(
+            create.starts_with(
+                "distrobox create --yes --image docker.io/library/ubuntu:24.04 --name ubuntu-24.04"
+            ),
+            "{create}"
+        );
+
+        assert!(store.quick_create("not-a-distro").is_err());
+    }
+
+    #[gtk::test]
+    fn test_check_terminal_detects_wrong_separator() {
+        let marker = terminal_check_marker_path().to_string_lossy().to_string();
+        let runner = NullCommandRunnerBuilder::new()
+            .cmd(&["cat", marker.as_str()], TERMINAL_CHECK_TEXT)
+            .build();
+        let store = RootStore::new(runner);
+        // The null runner doesn't run anything, so the marker is only "written"
+        // when the stubbed `cat` answers, as if the terminal ran the command
+        let working = Terminal {
+            name: "Working".into(),
+            program: "working-terminal".into(),
+            extra_args: vec![],
+            separator_arg: "--".into(),
+            read_only: true,
+        };
+        let result = Rc::new(RefCell::new(None));
+        let result_clone = result.clone();
+        let store_clone = store.clone();
+        glib::spawn_future_local(async move {
+            let res = store_clone
+                .check_terminal(&working, Duration::from_millis(100))
+                .await;
+            result_clone.replace(Some(res));
+        });
+        spin_main_context_until(Duration::from_secs(1), || result.borrow().is_some());
+        assert!(result.borrow_mut().take().unwrap().is_ok());
+
+        let store = RootStore::new(NullCommandRunnerBuilder::new().build());
+        let misconfigured = Terminal {
+            name: "Misconfigured".into(),
+            program: "my-terminal".into(),
+            extra_args: vec![],
+            separator_arg: "-x".into(),
+            read_only: false,
+        };
+        let result_clone = result.clone();
+        glib::spawn_future_local(async move {
+            let res = store
+                .check_terminal(&misconfigured, Duration::from_millis(100))
+                .await;
+            result_clone.replace(Some(res));
+        });
+        spin_main_context_until(Duration::from_secs(1), || result.borrow().is_some());
+        let error = result.borrow_mut().take().unwrap().unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "'my-terminal' started, but didn't run the command. Check its separator argument '-x': terminals expect the command after an argument like '-e', '--' or '-x'."
+        );
+    }
+
     #[gtk::test]
     fn test_shortcuts_toggle_is_idempotent() {
         let store = RootStore::new(NullCommandRunnerBuilder::new().build());
@@ -1506,4 +3044,39 @@ mod tests {
             SHORTCUT_DEFINITIONS.len() as u32
         );
     }
+
+    #[gtk::test]
+    fn test_version_query_times_out_at_configured_duration() {
+        let store = RootStore::new(NullCommandRunnerBuilder::new().build());
+        store
+            .settings()
+            .set_uint("version-timeout", 1)
+            .expect("failed to set version-timeout setting");
+        spin_main_context_until(Duration::from_millis(10), || false);
+
+        store
+            .distrobox_version()
+            .set_fetcher(|| async { pending::<anyhow::Result<String>>().await });
+        let started = Instant::now();
+        store.distrobox_version().refetch();
+        spin_main_context_until(Duration::from_secs(3), || {
+            store.distrobox_version().error().is_some()
+        });
+        let elapsed = started.elapsed();
+        store.settings().reset("version-timeout");
+
+        let error = store
+            .distrobox_version()
+            .error()
+            .expect("query should time out");
+        assert_eq!(error.to_string(), "Query timed out after 1 seconds");
+        assert!(
+            elapsed >= Duration::from_secs(1),
+            "timed out early: {elapsed:?}"
+        );
+        assert!(
+            elapsed < Duration::from_secs(2),
+            "timed out late: {elapsed:?}"
+        );
+    }
 }
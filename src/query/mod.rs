@@ -206,6 +206,11 @@ where
         self
     }
 
+    /// Change the timeout duration of the next fetches (None = no timeout)
+    pub fn set_timeout(&self, timeout: Option<Duration>) {
+        self.inner.borrow_mut().timeout = timeout;
+    }
+
     /// Strategy: Execute fetch immediately
     pub fn immediate() -> impl Fn(&Query<T>) {
         |query: &Query<T>| {
@@ -544,6 +549,11 @@ where
         self.inner.borrow().data.clone()
     }
 
+    /// The error of the last fetch, if it failed
+    pub fn error(&self) -> Option<Rc<anyhow::Error>> {
+        self.inner.borrow().error.clone()
+    }
+
     /// Check if the cached data is stale based on a given max age
     /// Returns true if data has never been fetched or if the duration has elapsed
     pub fn is_stale(&self, max_age: Duration) -> bool {
@@ -0,0 +1,53 @@
+// Fixtures shared by the tests of the models and widgets
+
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use gtk::glib;
+
+use crate::backends::container_runtime::ContainerRuntime;
+use crate::backends::{ContainerInfo, Distrobox, Status};
+use crate::models::Container;
+use crate::query::Query;
+
+/// Iterates the main context until `condition` holds, or `timeout` elapses.
+/// On timeout, the pending events are still dispatched.
+pub fn spin_main_context_until(timeout: Duration, mut condition: impl FnMut() -> bool) {
+    let context = glib::MainContext::ref_thread_default();
+    let deadline = Instant::now() + timeout;
+
+    while Instant::now() < deadline {
+        while context.pending() {
+            context.iteration(false);
+        }
+        if condition() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    while context.pending() {
+        context.iteration(false);
+    }
+}
+
+/// A container as listed by `distrobox ls`, not reacting to changes of the list
+pub fn container(
+    distrobox: &Distrobox,
+    runtime_query: Query<Rc<dyn ContainerRuntime>>,
+    name: &str,
+    status: Status,
+    image: &str,
+) -> Container {
+    Container::from_info(
+        distrobox.clone(),
+        Rc::new(|| {}),
+        runtime_query,
+        ContainerInfo {
+            id: "1".into(),
+            name: name.into(),
+            status,
+            image: image.into(),
+        },
+    )
+}
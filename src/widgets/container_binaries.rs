@@ -0,0 +1,285 @@
+use crate::backends::ExportableBinary;
+use crate::gtk_utils::TypedListStore;
+use crate::i18n::gettext;
+use crate::models::container::EXPORTS_MAX_AGE;
+use crate::models::{Container, RootStore};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib::{BoxedAnyObject, clone};
+use gtk::{self, glib};
+use std::cell::OnceCell;
+
+mod imp {
+    use super::*;
+    use gtk::glib::{Properties, derived_properties};
+
+    // Lists the binaries exported from a container, backed by `Container::binaries`
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type=super::ContainerBinaries)]
+    pub struct ContainerBinaries {
+        #[property(get, set, construct_only)]
+        pub root_store: OnceCell<RootStore>,
+        #[property(get, set, construct_only)]
+        pub container: OnceCell<Container>,
+        pub binaries_group: adw::PreferencesGroup,
+        pub binary_name_entry: adw::EntryRow,
+        pub list_box: gtk::ListBox,
+        pub error_label: gtk::Label,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ContainerBinaries {
+        const NAME: &'static str = "ContainerBinaries";
+        type Type = super::ContainerBinaries;
+        type ParentType = adw::Bin;
+    }
+
+    #[derived_properties]
+    impl ObjectImpl for ContainerBinaries {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+    }
+
+    impl WidgetImpl for ContainerBinaries {}
+    impl BinImpl for ContainerBinaries {}
+}
+
+glib::wrapper! {
+    pub struct ContainerBinaries(ObjectSubclass<imp::ContainerBinaries>)
+        @extends adw::Bin, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ContainerBinaries {
+    pub fn new(root_store: &RootStore, container: &Container) -> Self {
+        let obj: Self = glib::Object::builder()
+            .property("root-store", root_store)
+            .property("container", container)
+            .build();
+        obj.set_child(Some(&obj.build_main_content()));
+        obj.bind_binaries_query();
+        obj
+    }
+
+    fn build_main_content(&self) -> gtk::Widget {
+        let imp = self.imp();
+
+        let scrolled_window = gtk::ScrolledWindow::new();
+        scrolled_window.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        scrolled_window.set_vexpand(true);
+
+        let main_box = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        main_box.set_margin_start(12);
+        main_box.set_margin_end(12);
+        main_box.set_margin_top(12);
+        main_box.set_margin_bottom(24);
+
+        let refresh_btn = gtk::Button::from_icon_name("view-refresh-symbolic");
+        refresh_btn.set_tooltip_text(Some(&gettext("Refresh")));
+        refresh_btn.set_valign(gtk::Align::Center);
+        refresh_btn.add_css_class("flat");
+        refresh_btn.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                this.container().binaries().refetch();
+            }
+        ));
+
+        // Binaries are looked up in the container's PATH, or given as absolute paths
+        imp.binary_name_entry
+            .set_title(&gettext("Export Binary (name or path)"));
+        imp.binary_name_entry.set_show_apply_button(true);
+        imp.binary_name_entry.connect_apply(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |entry| {
+                let binary = entry.text().trim().to_string();
+                if binary.is_empty() {
+                    return;
+                }
+                this.root_store().export_binary(&this.container(), &binary);
+                entry.set_text("");
+            }
+        ));
+
+        imp.list_box.add_css_class("boxed-list");
+        imp.list_box.set_selection_mode(gtk::SelectionMode::None);
+        imp.list_box.set_margin_top(12);
+
+        imp.binaries_group.set_title(&gettext("Exported Binaries"));
+        imp.binaries_group
+            .set_description(Some(&gettext("No exported binaries")));
+        imp.binaries_group.set_header_suffix(Some(&refresh_btn));
+        imp.binaries_group.add(&imp.binary_name_entry);
+        imp.binaries_group.add(&imp.list_box);
+
+        imp.error_label
+            .set_label(&gettext("Error loading exported binaries"));
+        imp.error_label.set_halign(gtk::Align::Start);
+        imp.error_label.set_wrap(true);
+        imp.error_label.add_css_class("error");
+        imp.error_label.set_visible(false);
+
+        main_box.append(&imp.binaries_group);
+        main_box.append(&imp.error_label);
+
+        let clamp = adw::Clamp::new();
+        clamp.set_child(Some(&main_box));
+        scrolled_window.set_child(Some(&clamp));
+
+        scrolled_window.upcast()
+    }
+
+    fn bind_binaries_query(&self) {
+        let binaries = self.container().binaries();
+
+        binaries.connect_success(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |binaries_data| {
+                this.imp().error_label.set_visible(false);
+                this.render_binaries(binaries_data);
+            }
+        ));
+        binaries.connect_error(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                this.imp().error_label.set_visible(true);
+            }
+        ));
+
+        if let Some(binaries_data) = binaries.data() {
+            self.render_binaries(&binaries_data);
+        }
+
+        // Listing binaries enters the container, so wait until the tab is actually shown
+        self.connect_map(|this| {
+            this.container()
+                .binaries()
+                .refetch_if_stale(EXPORTS_MAX_AGE);
+        });
+    }
+
+    fn render_binaries(&self, binaries_data: &TypedListStore<BoxedAnyObject>) {
+        let imp = self.imp();
+        if binaries_data.is_empty() {
+            imp.binaries_group
+                .set_description(Some(&gettext("No exported binaries")));
+        } else {
+            imp.binaries_group.set_description(None);
+        }
+
+        let this = self.clone();
+        imp.list_box
+            .bind_model(Some(binaries_data.inner()), move |obj| {
+                let binary = obj
+                    .downcast_ref::<BoxedAnyObject>()
+                    .map(|obj| obj.borrow::<ExportableBinary>())
+                    .unwrap();
+                this.build_row(&binary).upcast()
+            });
+    }
+
+    fn build_row(&self, binary: &ExportableBinary) -> adw::ActionRow {
+        let row = adw::ActionRow::new();
+        row.set_title(&binary.name);
+        row.set_subtitle(&format!(
+            "{} → {}",
+            binary.source_path, binary.exported_path
+        ));
+        row.set_subtitle_lines(2);
+
+        let unexport_btn = gtk::Button::from_icon_name("user-trash-symbolic");
+        unexport_btn.set_tooltip_text(Some(&gettext("Unexport Binary")));
+        unexport_btn.set_valign(gtk::Align::Center);
+        unexport_btn.add_css_class("flat");
+        let source_path = binary.source_path.clone();
+        unexport_btn.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |_| {
+                this.unexport(&source_path);
+            }
+        ));
+        row.add_suffix(&unexport_btn);
+
+        row
+    }
+
+    fn unexport(&self, source_path: &str) {
+        self.root_store()
+            .unexport_binary(&self.container(), source_path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::backends::Status;
+    use crate::fakers::NullCommandRunnerBuilder;
+    use crate::test_utils::{container, spin_main_context_until};
+
+    #[gtk::test]
+    fn test_renders_binaries_and_unexports() {
+        let runner = NullCommandRunnerBuilder::new()
+            .cmd(
+                &[
+                    "distrobox",
+                    "enter",
+                    "test-box",
+                    "--",
+                    "distrobox-export",
+                    "--list-binaries",
+                ],
+                "'/usr/bin/vim'       | /home/user/.local/bin/vim\n'/usr/bin/htop'      | /home/user/.local/bin/htop",
+            )
+            .build();
+        let tracker = runner.output_tracker();
+        tracker.enable();
+        let root_store = RootStore::new(runner);
+        let container = container(
+            root_store.distrobox(),
+            root_store.container_runtime(),
+            "test-box",
+            Status::Up("2 minutes ago".into()),
+            "docker.io/library/ubuntu:latest",
+        );
+        let widget = ContainerBinaries::new(&root_store, &container);
+
+        container.binaries().refetch();
+        spin_main_context_until(Duration::from_secs(1), || {
+            widget.imp().list_box.row_at_index(1).is_some()
+        });
+
+        let titles: Vec<String> = (0..)
+            .map_while(|i| widget.imp().list_box.row_at_index(i))
+            .map(|row| {
+                row.downcast::<adw::ActionRow>()
+                    .unwrap()
+                    .title()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(titles, ["vim", "htop"]);
+        assert_eq!(widget.imp().binaries_group.description(), None);
+
+        let unexport_command =
+            "distrobox enter --name test-box -- distrobox-export -d --bin /usr/bin/vim";
+        let has_unexported = || {
+            tracker.items().iter().any(|event| {
+                event
+                    .command()
+                    .is_some_and(|cmd| cmd.to_string() == unexport_command)
+            })
+        };
+        widget.unexport("/usr/bin/vim");
+        spin_main_context_until(Duration::from_secs(1), has_unexported);
+        assert!(has_unexported());
+    }
+}
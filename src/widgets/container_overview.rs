@@ -1,5 +1,8 @@
+use crate::backends::container_runtime::{Mount, NetworkInfo};
+use crate::gettext_f;
 use crate::i18n::gettext;
-use crate::models::Container;
+use crate::models::image_reference::image_uses_latest;
+use crate::models::{Container, RootStore};
 use crate::widgets::DistroShelfWindow;
 
 use crate::gtk_utils::reaction;
@@ -7,7 +10,7 @@ use adw::prelude::*;
 use adw::subclass::prelude::*;
 use gtk::glib::clone;
 use gtk::{self, gdk, glib, pango};
-use std::cell::OnceCell;
+use std::cell::{OnceCell, RefCell};
 
 mod imp {
     use super::*;
@@ -17,6 +20,8 @@ mod imp {
     #[derive(Default, Properties)]
     #[properties(wrapper_type=super::ContainerOverview)]
     pub struct ContainerOverview {
+        #[property(get, set, construct_only)]
+        pub root_store: OnceCell<RootStore>,
         #[property(get, set, construct)]
         pub container: OnceCell<Container>,
     }
@@ -47,8 +52,9 @@ glib::wrapper! {
 }
 
 impl ContainerOverview {
-    pub fn new(container: &Container) -> Self {
+    pub fn new(root_store: &RootStore, container: &Container) -> Self {
         let obj: Self = glib::Object::builder()
+            .property("root-store", root_store)
             .property("container", container)
             .build();
         obj.set_child(Some(&obj.build_main_content(container)));
@@ -111,6 +117,50 @@ impl ContainerOverview {
             }
         ));
 
+        // Network row, useful to reach services (like sshd) running in the container
+        let network_row = adw::ActionRow::new();
+        network_row.set_title(&gettext("Network"));
+        network_row.set_subtitle_selectable(true);
+        network_row.set_visible(false);
+        status_group.add(&network_row);
+
+        let network_query = container.network_info();
+        network_query.connect_success(clone!(
+            #[weak]
+            network_row,
+            move |info| {
+                network_row.set_subtitle(&format_network_info(info));
+                network_row.set_visible(true);
+            }
+        ));
+        network_query.connect_error(clone!(
+            #[weak]
+            network_row,
+            move |_| {
+                network_row.set_visible(false);
+            }
+        ));
+
+        // A moving tag may give a different image when the container gets recreated
+        if image_uses_latest(&container.image()) {
+            let latest_row = adw::ActionRow::new();
+            latest_row.set_title(&gettext("Image Not Pinned"));
+            latest_row.set_subtitle(&gettext(
+                "The latest tag can point to a different image over time",
+            ));
+            latest_row.add_css_class("dim-label");
+            latest_row.add_prefix(&gtk::Image::from_icon_name("dialog-warning-symbolic"));
+            let pin_btn = gtk::Button::with_label(&gettext("Pin to Current Digest"));
+            pin_btn.set_tooltip_text(Some(&gettext(
+                "Recreate this container from the exact image it was created from",
+            )));
+            pin_btn.set_valign(gtk::Align::Center);
+            pin_btn.add_css_class("flat");
+            pin_btn.set_action_name(Some("win.pin-image-digest"));
+            latest_row.add_suffix(&pin_btn);
+            status_group.add(&latest_row);
+        }
+
         reaction! {
             (container.status_detail(), container.status_tag()),
             move |(detail, tag): (String, String)| {
@@ -119,10 +169,70 @@ impl ContainerOverview {
                 stop_btn.set_visible(tag == "up");
                 if tag == "up" {
                     usage_query.fetch();
+                    network_query.fetch();
                 }
             }
         };
 
+        // Shared paths group, distrobox shares the home folder and more by default
+        let shared_group = adw::PreferencesGroup::new();
+        shared_group.set_title(&gettext("Shared With the Host"));
+        shared_group.set_description(Some(&gettext(
+            "Host paths this container can access. Changes made inside them affect the host.",
+        )));
+        shared_group.set_visible(false);
+        let shared_list = gtk::ListBox::new();
+        shared_list.set_selection_mode(gtk::SelectionMode::None);
+        shared_list.add_css_class("boxed-list");
+        shared_group.add(&shared_list);
+
+        let mounts_query = container.mounts();
+        mounts_query.connect_success(clone!(
+            #[weak]
+            shared_group,
+            #[weak]
+            shared_list,
+            move |mounts| {
+                let home = glib::home_dir().to_string_lossy().into_owned();
+                let paths = shared_paths(mounts, &home);
+                shared_list.remove_all();
+                for path in &paths {
+                    shared_list.append(&build_shared_path_row(path));
+                }
+                shared_group.set_visible(!paths.is_empty());
+            }
+        ));
+        mounts_query.fetch();
+
+        // Recently launched apps, relaunched with one click
+        let recent_group = adw::PreferencesGroup::new();
+        recent_group.set_title(&gettext("Recent Apps"));
+        let recent_list = gtk::ListBox::new();
+        recent_list.set_selection_mode(gtk::SelectionMode::None);
+        recent_list.add_css_class("boxed-list");
+        recent_group.add(&recent_list);
+        self.render_recent_apps(&recent_group, &recent_list);
+        // The content is rebuilt for every selected container, stop following the history
+        // once this one is gone
+        let settings = self.root_store().settings();
+        let history_handler = RefCell::new(Some(settings.connect_changed(
+            Some("launch-history"),
+            clone!(
+                #[weak(rename_to=this)]
+                self,
+                #[weak]
+                recent_group,
+                #[weak]
+                recent_list,
+                move |_, _| this.render_recent_apps(&recent_group, &recent_list)
+            ),
+        )));
+        recent_group.connect_destroy(move |_| {
+            if let Some(handler) = history_handler.take() {
+                settings.disconnect(handler);
+            }
+        });
+
         // Quick Actions Group
         let actions_group = adw::PreferencesGroup::new();
         actions_group.set_title(&gettext("Quick Actions"));
@@ -155,6 +265,35 @@ impl ContainerOverview {
             actions_group.add(&install_package_row);
         }
 
+        let timezone_row = self.create_button_row(
+            &gettext("Sync Timezone"),
+            "preferences-system-time-symbolic",
+            &gettext("Use the timezone of the host"),
+            "win.sync-timezone",
+        );
+        actions_group.add(&timezone_row);
+
+        // Run instead of the shell when opening a terminal, e.g. tmux
+        let default_command_row = adw::EntryRow::new();
+        default_command_row.set_title(&gettext("Terminal Command (empty for a shell)"));
+        default_command_row.set_show_apply_button(true);
+        default_command_row.add_prefix(&gtk::Image::from_icon_name("utilities-terminal-symbolic"));
+        default_command_row.set_text(
+            &self
+                .root_store()
+                .container_default_command(&container.name())
+                .unwrap_or_default(),
+        );
+        default_command_row.connect_apply(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |row| {
+                this.root_store()
+                    .set_container_default_command(&this.container().name(), &row.text());
+            }
+        ));
+        actions_group.add(&default_command_row);
+
         let clone_row = self.create_button_row(
             &gettext("Clone Container"),
             "edit-copy-symbolic",
@@ -163,6 +302,14 @@ impl ContainerOverview {
         );
         actions_group.add(&clone_row);
 
+        let copy_volumes_row = self.create_button_row(
+            &gettext("Copy Volumes To…"),
+            "folder-remote-symbolic",
+            &gettext("Create a container sharing the same volumes"),
+            "win.copy-volumes",
+        );
+        actions_group.add(&copy_volumes_row);
+
         // Danger Zone Group
         let danger_group = adw::PreferencesGroup::new();
         danger_group.set_title(&gettext("Danger Zone"));
@@ -178,9 +325,26 @@ impl ContainerOverview {
 
         danger_group.add(&delete_row);
 
+        let protected_row = adw::SwitchRow::new();
+        protected_row.set_title(&gettext("Protected"));
+        protected_row.set_subtitle(&gettext("Require typing the name before deleting"));
+        protected_row.add_prefix(&gtk::Image::from_icon_name("changes-prevent-symbolic"));
+        protected_row.set_active(container.protected());
+        protected_row.connect_active_notify(clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |row| {
+                this.root_store()
+                    .set_container_protected(&this.container(), row.is_active());
+            }
+        ));
+        danger_group.add(&protected_row);
+
         // Add all groups to main box
         main_box.append(&self.build_container_header(container));
         main_box.append(&status_group);
+        main_box.append(&shared_group);
+        main_box.append(&recent_group);
         main_box.append(&actions_group);
         main_box.append(&danger_group);
 
@@ -191,6 +355,29 @@ impl ContainerOverview {
         scrolled_window.upcast()
     }
 
+    fn render_recent_apps(&self, group: &adw::PreferencesGroup, list: &gtk::ListBox) {
+        let history = self.root_store().launch_history();
+        let recent = history.recent(&self.container().name());
+        list.remove_all();
+        for app in recent {
+            let row = adw::ActionRow::new();
+            row.set_title(&app.name);
+            row.set_activatable(true);
+            row.add_suffix(&gtk::Image::from_icon_name("media-playback-start-symbolic"));
+            let desktop_file_path = app.desktop_file_path.clone();
+            row.connect_activated(clone!(
+                #[weak(rename_to=this)]
+                self,
+                move |_| {
+                    this.root_store()
+                        .launch_recent_app(&this.container(), &desktop_file_path);
+                }
+            ));
+            list.append(&row);
+        }
+        group.set_visible(!recent.is_empty());
+    }
+
     pub fn build_container_header(&self, container: &Container) -> gtk::Box {
         // Create labels for the title and subtitle
         let title_label = gtk::Label::new(Some(&container.name()));
@@ -272,3 +459,170 @@ impl ContainerOverview {
         row
     }
 }
+
+/// A host path the container can access, as listed in the overview
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SharedPath {
+    host_path: String,
+    container_path: String,
+    read_only: bool,
+    /// What the path is, for the well known distrobox mounts
+    description: Option<String>,
+}
+
+// Mounted by distrobox to integrate the container with the host, not interesting to the user
+const INTEGRATION_MOUNTS: &[&str] = &[
+    "/dev",
+    "/dev/pts",
+    "/sys",
+    "/etc/hosts",
+    "/etc/hostname",
+    "/etc/resolv.conf",
+    "/etc/localtime",
+    "/run/.containerenv",
+];
+
+fn shared_paths(mounts: &[Mount], host_home: &str) -> Vec<SharedPath> {
+    let mut paths: Vec<SharedPath> = mounts
+        .iter()
+        .filter(|mount| mount.kind == "bind")
+        .filter(|mount| {
+            // distrobox-init and friends are mounted as single files in /usr/bin
+            !INTEGRATION_MOUNTS.contains(&mount.destination.as_str())
+                && !mount.destination.starts_with("/usr/")
+        })
+        .map(|mount| {
+            let description = if mount.source == host_home {
+                Some(gettext("Your home folder"))
+            } else if mount.source == "/" {
+                Some(gettext("The whole host filesystem"))
+            } else if mount.destination == "/tmp" {
+                Some(gettext("Temporary files"))
+            } else if mount.destination.starts_with("/run/user/") {
+                Some(gettext("Session runtime files, like sockets"))
+            } else {
+                None
+            };
+            SharedPath {
+                host_path: mount.source.clone(),
+                container_path: mount.destination.clone(),
+                read_only: mount.read_only,
+                description,
+            }
+        })
+        .collect();
+    // The home folder first, as it's the one users don't expect to be shared
+    paths.sort_by_key(|path| path.host_path != host_home);
+    paths
+}
+
+fn build_shared_path_row(path: &SharedPath) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_use_markup(false);
+    row.set_title(path.description.as_deref().unwrap_or(&path.container_path));
+    let subtitle = if path.host_path == path.container_path {
+        path.host_path.clone()
+    } else {
+        format!("{} → {}", path.host_path, path.container_path)
+    };
+    row.set_subtitle(&subtitle);
+    row.set_subtitle_selectable(true);
+    if path.read_only {
+        let read_only = gtk::Label::new(Some(&gettext("Read-only")));
+        read_only.add_css_class("dim-label");
+        read_only.add_css_class("caption");
+        row.add_suffix(&read_only);
+    }
+    row
+}
+
+fn format_network_info(info: &NetworkInfo) -> String {
+    let mut parts = vec![];
+    if info.host_network {
+        parts.push(gettext("Shares the host network"));
+    } else if info.ip_addresses.is_empty() {
+        parts.push(gettext("No IP address"));
+    } else {
+        parts.push(gettext_f!(
+            "IP: {addresses}",
+            "addresses" => info.ip_addresses.join(", ")
+        ));
+    }
+    for port in &info.ports {
+        let host_ip = if port.host_ip.is_empty() {
+            "0.0.0.0"
+        } else {
+            &port.host_ip
+        };
+        parts.push(format!(
+            "{}:{} → {}",
+            host_ip, port.host_port, port.container_port
+        ));
+    }
+    parts.join(" • ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(source: &str, destination: &str, read_only: bool) -> Mount {
+        Mount {
+            kind: "bind".into(),
+            source: source.into(),
+            destination: destination.into(),
+            read_only,
+        }
+    }
+
+    #[test]
+    fn shared_paths_from_mounts() {
+        let mounts = [
+            bind("/", "/run/host", true),
+            bind("/dev", "/dev", false),
+            bind("/usr/bin/distrobox-init", "/usr/bin/entrypoint", true),
+            bind("/home/me", "/home/me", false),
+            bind("/tmp", "/tmp", false),
+            bind("/etc/hosts", "/etc/hosts", true),
+            bind("/srv/projects", "/projects", false),
+            Mount {
+                kind: "volume".into(),
+                source: "/var/lib/containers/storage/volumes/cache/_data".into(),
+                destination: "/cache".into(),
+                read_only: false,
+            },
+        ];
+
+        let paths = shared_paths(&mounts, "/home/me");
+
+        assert_eq!(
+            paths,
+            [
+                SharedPath {
+                    host_path: "/home/me".into(),
+                    container_path: "/home/me".into(),
+                    read_only: false,
+                    description: Some("Your home folder".into()),
+                },
+                SharedPath {
+                    host_path: "/".into(),
+                    container_path: "/run/host".into(),
+                    read_only: true,
+                    description: Some("The whole host filesystem".into()),
+                },
+                SharedPath {
+                    host_path: "/tmp".into(),
+                    container_path: "/tmp".into(),
+                    read_only: false,
+                    description: Some("Temporary files".into()),
+                },
+                SharedPath {
+                    host_path: "/srv/projects".into(),
+                    container_path: "/projects".into(),
+                    read_only: false,
+                    description: None,
+                },
+            ]
+        );
+    }
+}
@@ -1,7 +1,10 @@
+mod container_binaries;
 mod container_overview;
 mod distro_icon;
 mod image_row_item;
 mod integrated_terminal;
+mod notifications_popover;
+mod quick_create_popover;
 mod sidebar_row;
 mod task_output_terminal;
 mod tasks_button;
@@ -9,10 +12,13 @@ mod terminal_combo_row;
 mod welcome_view;
 mod window;
 
+pub use container_binaries::ContainerBinaries;
 pub use container_overview::ContainerOverview;
 pub use distro_icon::DistroIcon;
 pub use image_row_item::ImageRowItem;
 pub use integrated_terminal::IntegratedTerminal;
+pub use notifications_popover::NotificationsPopover;
+pub use quick_create_popover::QuickCreatePopover;
 pub use sidebar_row::SidebarRow;
 pub use task_output_terminal::TaskOutputTerminal;
 pub use tasks_button::TasksButton;
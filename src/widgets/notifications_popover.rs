@@ -0,0 +1,134 @@
+use crate::i18n::gettext;
+use crate::models::{Notification, NotificationLevel, RootStore};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib;
+use gtk::glib::{Properties, clone, derived_properties};
+use std::cell::RefCell;
+
+mod imp {
+    use super::*;
+
+    // Lists the recent errors and warnings, which outlive their toasts
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::NotificationsPopover)]
+    pub struct NotificationsPopover {
+        #[property(get, set, construct)]
+        pub root_store: RefCell<RootStore>,
+        pub list_box: gtk::ListBox,
+        pub clear_btn: gtk::Button,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for NotificationsPopover {
+        const NAME: &'static str = "NotificationsPopover";
+        type Type = super::NotificationsPopover;
+        type ParentType = gtk::Popover;
+    }
+
+    #[derived_properties]
+    impl ObjectImpl for NotificationsPopover {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            let vbox = gtk::Box::new(gtk::Orientation::Vertical, 6);
+            vbox.set_margin_start(6);
+            vbox.set_margin_end(6);
+            vbox.set_margin_top(6);
+            vbox.set_margin_bottom(6);
+
+            let header = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            let title = gtk::Label::new(Some(&gettext("Notifications")));
+            title.set_halign(gtk::Align::Start);
+            title.set_hexpand(true);
+            title.add_css_class("heading");
+            header.append(&title);
+
+            self.clear_btn.set_label(&gettext("Clear"));
+            self.clear_btn.add_css_class("flat");
+            self.clear_btn.connect_clicked(clone!(
+                #[weak]
+                obj,
+                move |_| obj.root_store().clear_notifications()
+            ));
+            header.append(&self.clear_btn);
+            vbox.append(&header);
+
+            let placeholder = gtk::Label::new(Some(&gettext("No Notifications")));
+            placeholder.add_css_class("dim-label");
+            placeholder.set_margin_top(12);
+            placeholder.set_margin_bottom(12);
+            self.list_box.set_placeholder(Some(&placeholder));
+            self.list_box.set_selection_mode(gtk::SelectionMode::None);
+            self.list_box.add_css_class("boxed-list");
+
+            let notifications = obj.root_store().notifications().clone();
+            self.list_box
+                .bind_model(Some(notifications.inner()), move |obj| {
+                    let notification = obj.downcast_ref::<Notification>().unwrap();
+                    super::build_row(notification).upcast()
+                });
+
+            let scrolled_window = gtk::ScrolledWindow::new();
+            scrolled_window.set_hscrollbar_policy(gtk::PolicyType::Never);
+            scrolled_window.set_propagate_natural_height(true);
+            scrolled_window.set_max_content_height(400);
+            scrolled_window.set_min_content_width(360);
+            scrolled_window.set_child(Some(&self.list_box));
+            vbox.append(&scrolled_window);
+
+            obj.set_child(Some(&vbox));
+
+            self.clear_btn.set_sensitive(!notifications.is_empty());
+            notifications.inner().connect_items_changed(clone!(
+                #[weak]
+                obj,
+                move |list, _, _, _| obj.imp().clear_btn.set_sensitive(list.n_items() > 0)
+            ));
+        }
+    }
+
+    impl WidgetImpl for NotificationsPopover {}
+    impl PopoverImpl for NotificationsPopover {}
+}
+
+glib::wrapper! {
+    pub struct NotificationsPopover(ObjectSubclass<imp::NotificationsPopover>)
+        @extends gtk::Popover, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::ShortcutManager;
+}
+
+impl NotificationsPopover {
+    pub fn new(root_store: &RootStore) -> Self {
+        glib::Object::builder()
+            .property("root-store", root_store)
+            .build()
+    }
+}
+
+fn build_row(notification: &Notification) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    // Error messages may contain anything, including markup characters
+    row.set_use_markup(false);
+    row.set_title(&notification.title());
+    row.set_subtitle(&notification.message());
+    row.set_subtitle_lines(4);
+
+    let icon_name = match notification.level() {
+        NotificationLevel::Error => "dialog-error-symbolic",
+        NotificationLevel::Warning => "dialog-warning-symbolic",
+    };
+    let icon = gtk::Image::from_icon_name(icon_name);
+    icon.add_css_class(match notification.level() {
+        NotificationLevel::Error => "error",
+        NotificationLevel::Warning => "warning",
+    });
+    row.add_prefix(&icon);
+
+    let time = gtk::Label::new(Some(&notification.time_label()));
+    time.add_css_class("dim-label");
+    time.add_css_class("caption");
+    row.add_suffix(&time);
+    row
+}
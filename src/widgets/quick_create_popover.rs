@@ -0,0 +1,142 @@
+use crate::backends::{CreateArgs, CreateArgsImage};
+use crate::gettext_f;
+use crate::i18n::gettext;
+use crate::models::RootStore;
+use crate::models::image_reference::derive_container_name;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib;
+use gtk::glib::{Properties, clone, derived_properties};
+use std::cell::RefCell;
+
+mod imp {
+    use super::*;
+
+    // Compact alternative to the create dialog: paste an image, press Enter
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::QuickCreatePopover)]
+    pub struct QuickCreatePopover {
+        #[property(get, set, construct)]
+        pub root_store: RefCell<RootStore>,
+        pub image_entry: gtk::Entry,
+        pub name_label: gtk::Label,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for QuickCreatePopover {
+        const NAME: &'static str = "QuickCreatePopover";
+        type Type = super::QuickCreatePopover;
+        type ParentType = gtk::Popover;
+    }
+
+    #[derived_properties]
+    impl ObjectImpl for QuickCreatePopover {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            let vbox = gtk::Box::new(gtk::Orientation::Vertical, 6);
+            vbox.set_margin_start(6);
+            vbox.set_margin_end(6);
+            vbox.set_margin_top(6);
+            vbox.set_margin_bottom(6);
+
+            let title = gtk::Label::new(Some(&gettext("Quick Create")));
+            title.set_halign(gtk::Align::Start);
+            title.add_css_class("heading");
+            vbox.append(&title);
+
+            self.image_entry
+                .set_placeholder_text(Some("docker.io/library/ubuntu:24.04"));
+            self.image_entry.set_width_chars(32);
+            vbox.append(&self.image_entry);
+
+            self.name_label.set_halign(gtk::Align::Start);
+            self.name_label.set_wrap(true);
+            self.name_label.add_css_class("dim-label");
+            self.name_label.add_css_class("caption");
+            self.name_label
+                .set_label(&gettext("Paste an image reference and press Enter"));
+            vbox.append(&self.name_label);
+
+            obj.set_child(Some(&vbox));
+
+            self.image_entry.connect_changed(clone!(
+                #[weak]
+                obj,
+                move |_| obj.update_name_preview()
+            ));
+            self.image_entry.connect_activate(clone!(
+                #[weak]
+                obj,
+                move |_| obj.create()
+            ));
+        }
+    }
+
+    impl WidgetImpl for QuickCreatePopover {}
+    impl PopoverImpl for QuickCreatePopover {}
+}
+
+glib::wrapper! {
+    pub struct QuickCreatePopover(ObjectSubclass<imp::QuickCreatePopover>)
+        @extends gtk::Popover, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::ShortcutManager;
+}
+
+impl QuickCreatePopover {
+    pub fn new(root_store: &RootStore) -> Self {
+        glib::Object::builder()
+            .property("root-store", root_store)
+            .build()
+    }
+
+    fn update_name_preview(&self) {
+        let imp = self.imp();
+        let image = imp.image_entry.text();
+        if image.trim().is_empty() {
+            imp.image_entry.remove_css_class("error");
+            imp.name_label
+                .set_label(&gettext("Paste an image reference and press Enter"));
+            return;
+        }
+        match derive_container_name(&image) {
+            Some(name) => {
+                let name = self.root_store().available_container_name(name);
+                imp.image_entry.remove_css_class("error");
+                imp.name_label.set_label(&gettext_f!(
+                    "Container name: {name}",
+                    "name" => name
+                ));
+            }
+            None => {
+                imp.image_entry.add_css_class("error");
+                imp.name_label
+                    .set_label(&gettext("Can't derive a container name from this image"));
+            }
+        }
+    }
+
+    fn create(&self) {
+        let imp = self.imp();
+        let image = imp.image_entry.text();
+        let Some(name) = derive_container_name(&image) else {
+            imp.image_entry.add_css_class("error");
+            return;
+        };
+        let Ok(image) = CreateArgsImage::new(image.trim()) else {
+            imp.image_entry.add_css_class("error");
+            return;
+        };
+
+        let create_args = CreateArgs {
+            name: self.root_store().available_container_name(name),
+            image: Some(image),
+            ..Default::default()
+        };
+        self.root_store().create_container(create_args);
+
+        imp.image_entry.set_text("");
+        self.popdown();
+    }
+}
@@ -4,7 +4,7 @@ use adw::prelude::*;
 use adw::subclass::prelude::*;
 use gtk::{self, glib, pango};
 
-use crate::{models::Container, widgets::DistroIcon};
+use crate::{i18n::gettext, models::Container, widgets::DistroIcon};
 
 mod imp {
     use std::cell::RefCell;
@@ -24,6 +24,8 @@ mod imp {
         pub text_box: gtk::Box,
         pub status_overlay: gtk::Overlay,
         pub status_dot: gtk::Box,
+        pub task_spinner: adw::Spinner,
+        pub protected_icon: gtk::Image,
 
         #[property(get, set=Self::set_container)]
         pub container: RefCell<Container>,
@@ -49,6 +51,14 @@ mod imp {
                 .bind_property("image", &self.obj().clone(), "image")
                 .sync_create()
                 .build();
+            value
+                .bind_property("has-active-task", &self.task_spinner, "visible")
+                .sync_create()
+                .build();
+            value
+                .bind_property("protected", &self.protected_icon, "visible")
+                .sync_create()
+                .build();
         }
         fn set_image(&self, value: &str) {
             self.image.replace(value.to_string());
@@ -89,6 +99,8 @@ mod imp {
                 text_box: gtk::Box::new(gtk::Orientation::Vertical, 4),
                 status_overlay: gtk::Overlay::new(),
                 status_dot: gtk::Box::new(gtk::Orientation::Horizontal, 0),
+                task_spinner: adw::Spinner::new(),
+                protected_icon: gtk::Image::from_icon_name("changes-prevent-symbolic"),
                 container: Default::default(),
                 status_tag: Default::default(),
                 image: Default::default(),
@@ -144,7 +156,20 @@ mod imp {
             self.status_overlay.set_child(Some(&content_box));
             self.status_overlay.add_overlay(&self.status_dot);
 
+            // Shown while a task (upgrade, stop...) is running on the container
+            self.task_spinner.set_valign(gtk::Align::Center);
+            self.task_spinner.set_visible(false);
+            self.task_spinner
+                .set_tooltip_text(Some(&gettext("Operation in progress")));
+
+            self.protected_icon.set_valign(gtk::Align::Center);
+            self.protected_icon.set_visible(false);
+            self.protected_icon
+                .set_tooltip_text(Some(&gettext("Protected")));
+
             obj.append(&self.status_overlay);
+            obj.append(&self.protected_icon);
+            obj.append(&self.task_spinner);
         }
     }
 
@@ -169,3 +194,21 @@ impl SidebarRow {
         obj
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gtk::test]
+    fn test_spinner_follows_active_task() {
+        let container = Container::new();
+        let row = SidebarRow::new(&container);
+        assert!(!row.imp().task_spinner.is_visible());
+
+        container.set_has_active_task(true);
+        assert!(row.imp().task_spinner.is_visible());
+
+        container.set_has_active_task(false);
+        assert!(!row.imp().task_spinner.is_visible());
+    }
+}
@@ -1,8 +1,9 @@
 // Generated by RustGObjectGenerator
 // This file is licensed under the same terms as the project it belongs to
 
+use crate::gettext_f;
 use crate::i18n::gettext;
-use crate::models::{DialogType, RootStore, ViewType};
+use crate::models::{DistroboxTask, RootStore, TaskStatus, ViewType};
 use crate::widgets::TerminalComboRow;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
@@ -11,6 +12,47 @@ use glib::clone;
 use gtk::{gio, glib};
 use std::cell::RefCell;
 
+/// Events of the bundled distrobox download, as reported by its task
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadEvent {
+    Progress(f64),
+    Completed,
+    Failed(String),
+}
+
+/// State of the bundled distrobox download shown in the welcome view
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum DownloadState {
+    #[default]
+    Idle,
+    Downloading(f64),
+    Finished,
+    Failed(String),
+}
+
+impl DownloadState {
+    pub fn apply(self, event: DownloadEvent) -> Self {
+        match (self, event) {
+            // Late progress notifications don't reopen an ended download
+            (state @ (Self::Finished | Self::Failed(_)), DownloadEvent::Progress(_)) => state,
+            (_, DownloadEvent::Progress(fraction)) => Self::Downloading(fraction.clamp(0.0, 1.0)),
+            (_, DownloadEvent::Completed) => Self::Finished,
+            (_, DownloadEvent::Failed(message)) => Self::Failed(message),
+        }
+    }
+
+    fn event_for_task(task: &DistroboxTask) -> DownloadEvent {
+        match task.status() {
+            TaskStatus::Successful => DownloadEvent::Completed,
+            TaskStatus::Failed => DownloadEvent::Failed(
+                task.error_message()
+                    .unwrap_or_else(|| gettext("Download failed")),
+            ),
+            TaskStatus::Pending | TaskStatus::Executing => DownloadEvent::Progress(task.progress()),
+        }
+    }
+}
+
 mod imp {
 
     use super::*;
@@ -49,6 +91,10 @@ mod imp {
         continue_btn: TemplateChild<gtk::Button>,
         #[template_child]
         refresh_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        download_progress_bar: TemplateChild<gtk::ProgressBar>,
+
+        pub download_state: RefCell<DownloadState>,
 
         runtime_status_icon: RefCell<Option<gtk::Image>>,
         distrobox_status_icon: RefCell<Option<gtk::Image>>,
@@ -325,46 +371,61 @@ mod imp {
             }
         }
         #[template_callback]
-        fn use_bundled_version(&self, btn: &gtk::Button) {
+        fn use_bundled_version(&self, _: &gtk::Button) {
             let obj = self.obj();
 
-            // Show spinner in button
-            let spinner = adw::Spinner::new();
-            btn.set_child(Some(&spinner));
-            btn.set_sensitive(false);
-
             // Directly trigger the download and get the task
             let task = obj.root_store().download_distrobox();
-
-            // Connect to the task status changes
-            task.connect_status_notify(clone!(
-                #[weak]
-                obj,
-                #[strong]
-                btn,
-                move |task| {
-                    if task.is_successful() {
-                        obj.imp()
-                            .carousel
-                            .scroll_to(&*obj.imp().terminal_preferences_page, true);
-                    } else if task.is_failed() {
-                        btn.set_child(Some(&gtk::Label::new(Some(&gettext(
-                            "Use Bundled Version",
-                        )))));
-                        btn.set_sensitive(true);
-                        obj.set_distrobox_error(Some(gettext("Download failed")));
-                    }
-                }
-            ));
-
-            // Open task manager dialog from welcome view
-            obj.root_store().set_current_dialog(DialogType::TaskManager);
+            obj.set_distrobox_error(None::<String>);
+            obj.track_download(&task);
 
             // Set the preference for future launches
             let settings = gio::Settings::new("com.ranfdev.DistroShelf");
             let _ = settings.set_string("distrobox-executable", "bundled");
         }
     }
+
+    impl WelcomeView {
+        pub(super) fn render_download_state(&self) {
+            let state = self.download_state.borrow().clone();
+            let progress_bar = &self.download_progress_bar;
+            match state {
+                DownloadState::Idle => {
+                    progress_bar.set_visible(false);
+                }
+                DownloadState::Downloading(fraction) => {
+                    progress_bar.set_visible(true);
+                    progress_bar.set_fraction(fraction);
+                    progress_bar.set_text(Some(&gettext_f!(
+                        "Downloading distrobox… {percent}%",
+                        "percent" => format!("{:.0}", fraction * 100.0)
+                    )));
+                    self.refresh_btn.set_sensitive(false);
+                }
+                DownloadState::Finished => {
+                    progress_bar.set_fraction(1.0);
+                    progress_bar.set_text(Some(&gettext("Distrobox installed")));
+                    self.refresh_btn.set_sensitive(true);
+                    let root_store = self.obj().root_store();
+                    // The runtime may not have been found before distrobox was installed
+                    if root_store.container_runtime().data().is_none() {
+                        root_store.container_runtime().refetch();
+                    }
+                    root_store.load_containers();
+                    self.carousel
+                        .scroll_to(&*self.terminal_preferences_page, true);
+                }
+                DownloadState::Failed(message) => {
+                    progress_bar.set_visible(false);
+                    self.refresh_btn.set_sensitive(true);
+                    self.obj().set_distrobox_error(Some(gettext_f!(
+                        "Download failed: {message}",
+                        "message" => message
+                    )));
+                }
+            }
+        }
+    }
 }
 
 glib::wrapper! {
@@ -372,3 +433,50 @@ glib::wrapper! {
         @extends adw::Bin, gtk::Widget,
         @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
 }
+
+impl WelcomeView {
+    fn track_download(&self, task: &DistroboxTask) {
+        self.imp().download_state.replace(DownloadState::Idle);
+        let on_task_changed = clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |task: &DistroboxTask| {
+                this.handle_download_event(DownloadState::event_for_task(task));
+            }
+        );
+        on_task_changed(task);
+        task.connect_progress_notify(on_task_changed.clone());
+        task.connect_status_notify(on_task_changed);
+    }
+
+    fn handle_download_event(&self, event: DownloadEvent) {
+        let imp = self.imp();
+        let state = imp.download_state.take().apply(event);
+        imp.download_state.replace(state);
+        imp.render_download_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn download_state_follows_events() {
+        let state = DownloadState::default()
+            .apply(DownloadEvent::Progress(0.0))
+            .apply(DownloadEvent::Progress(0.5));
+        assert_eq!(state, DownloadState::Downloading(0.5));
+
+        let state = state.apply(DownloadEvent::Completed);
+        assert_eq!(state, DownloadState::Finished);
+        // A progress notification arriving after completion is ignored
+        assert_eq!(
+            state.apply(DownloadEvent::Progress(1.0)),
+            DownloadState::Finished
+        );
+
+        let state = DownloadState::Downloading(0.2).apply(DownloadEvent::Failed("offline".into()));
+        assert_eq!(state, DownloadState::Failed("offline".into()));
+    }
+}
@@ -19,22 +19,31 @@
  */
 
 use crate::dialogs::{
-    CommandLogDialog, CreateDistroboxDialog, ExportableAppsDialog, PreferencesDialog,
-    TaskManagerDialog,
+    AdoptContainerDialog, CommandLogDialog, CreateDistroboxDialog, CreateWizardDialog,
+    ExportableAppsDialog, PreferencesDialog, TaskManagerDialog,
 };
+use crate::gettext_f;
 use crate::i18n::gettext;
-use crate::models::{Container, DialogParams, DialogType};
+use crate::models::known_distros::QUICK_CREATE_ENTRIES;
+use crate::models::{Container, DialogParams, DialogType, NotificationLevel};
 use crate::root_store::RootStore;
-use crate::widgets::{IntegratedTerminal, SidebarRow, TasksButton};
+use crate::widgets::{
+    ContainerBinaries, IntegratedTerminal, NotificationsPopover, QuickCreatePopover, SidebarRow,
+    TasksButton,
+};
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{Properties, derived_properties};
+use glib::{Properties, VariantTy, derived_properties};
 use gtk::gio::ActionEntry;
 use gtk::glib::clone;
 use gtk::{gio, glib};
 use std::cell::RefCell;
 use std::collections::HashMap;
-use tracing::info;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+// The first enter can install packages, so its setup may take a while
+const SETUP_TIMEOUT: Duration = Duration::from_secs(300);
 
 mod imp {
     use super::*;
@@ -52,7 +61,13 @@ mod imp {
         #[template_child]
         pub sidebar_stack: TemplateChild<gtk::Stack>,
         #[template_child]
-        pub create_distrobox_btn: TemplateChild<gtk::Button>,
+        pub create_distrobox_btn: TemplateChild<adw::SplitButton>,
+        #[template_child]
+        pub quick_create_btn: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub refreshing_spinner: TemplateChild<adw::Spinner>,
+        #[template_child]
+        pub notifications_btn: TemplateChild<gtk::MenuButton>,
         #[template_child]
         pub sidebar_bottom_slot: TemplateChild<adw::Bin>,
         #[template_child]
@@ -78,6 +93,8 @@ mod imp {
         #[template_child]
         pub overview_bin: TemplateChild<adw::Bin>,
         #[template_child]
+        pub binaries_bin: TemplateChild<adw::Bin>,
+        #[template_child]
         pub terminal_bin: TemplateChild<adw::Bin>,
         pub terminals_by_container: RefCell<HashMap<String, IntegratedTerminal>>,
     }
@@ -125,6 +142,19 @@ impl DistroShelfWindow {
         this.set_default_size(width, height);
 
         this.setup_gactions();
+        this.imp()
+            .create_distrobox_btn
+            .set_menu_model(Some(&quick_create_menu()));
+        this.imp()
+            .quick_create_btn
+            .set_popover(Some(&QuickCreatePopover::new(&this.root_store())));
+        this.imp()
+            .notifications_btn
+            .set_popover(Some(&NotificationsPopover::new(&this.root_store())));
+        this.root_store()
+            .bind_property("refreshing", &*this.imp().refreshing_spinner, "visible")
+            .sync_create()
+            .build();
         this.imp()
             .content_state_stack
             .set_visible_child_name("no_content");
@@ -179,6 +209,12 @@ impl DistroShelfWindow {
                             this_clone.root_store(),
                             params.clone_source,
                         );
+                        if let Some(source) = params.volumes_source {
+                            dialog.copy_volumes_from(&source);
+                        }
+                        if let Some(image) = params.image {
+                            dialog.select_image(&image);
+                        }
                         dialog.upcast()
                     }
                     DialogType::TaskManager => TaskManagerDialog::new(root_store).upcast(),
@@ -188,6 +224,12 @@ impl DistroShelfWindow {
                     DialogType::CommandLog => {
                         CommandLogDialog::new(this_clone.root_store()).upcast()
                     }
+                    DialogType::AdoptContainer => {
+                        AdoptContainerDialog::new(this_clone.root_store()).upcast()
+                    }
+                    DialogType::CreateWizard => {
+                        CreateWizardDialog::new(this_clone.root_store()).upcast()
+                    }
                     DialogType::None => return,
                 };
                 this_clone.set_current_dialog(Some(&dialog));
@@ -235,6 +277,9 @@ impl DistroShelfWindow {
             a("refresh").activate(move |this: &DistroShelfWindow, _, _| {
                 this.root_store().load_containers();
             }),
+            a("refresh-all").activate(move |this: &DistroShelfWindow, _, _| {
+                this.root_store().refresh_all();
+            }),
             a("upgrade-all").activate(move |this: &DistroShelfWindow, _, _| {
                 this.root_store().upgrade_all();
             }),
@@ -260,6 +305,29 @@ impl DistroShelfWindow {
             a("command-log").activate(|this, _, _| {
                 this.root_store().set_current_dialog(DialogType::CommandLog);
             }),
+            a("backup-exports").activate(|this, _, _| {
+                this.choose_exports_backup(true);
+            }),
+            a("restore-exports").activate(|this, _, _| {
+                this.choose_exports_backup(false);
+            }),
+            a("create-wizard").activate(|this, _, _| {
+                this.root_store()
+                    .set_current_dialog(DialogType::CreateWizard);
+            }),
+            a("quick-create")
+                .parameter_type(Some(VariantTy::STRING))
+                .activate(|this, _, param| {
+                    let distro = param.and_then(|p| p.str()).unwrap_or_default();
+                    if let Err(e) = this.root_store().quick_create(distro) {
+                        error!(error = %e, "Quick create failed");
+                        this.add_toast(adw::Toast::new(&e.to_string()));
+                    }
+                }),
+            a("adopt-container").activate(|this, _, _| {
+                this.root_store()
+                    .set_current_dialog(DialogType::AdoptContainer);
+            }),
             a("clone-container").activate(|this, _, _| {
                 if let Some(src) = this.root_store().selected_container() {
                     this.root_store().open_dialog(
@@ -268,12 +336,30 @@ impl DistroShelfWindow {
                     );
                 }
             }),
+            a("copy-volumes").activate(|this, _, _| {
+                if let Some(src) = this.root_store().selected_container() {
+                    this.root_store().open_dialog(
+                        DialogType::CreateDistrobox,
+                        DialogParams::new().with_volumes_source(src),
+                    );
+                }
+            }),
+            a("pin-image-digest").activate(|this, _, _| {
+                if let Some(container) = this.root_store().selected_container() {
+                    this.pin_image_digest(&container);
+                }
+            }),
             a("upgrade-container").activate(|this, _, _| {
                 if let Some(container) = this.root_store().selected_container() {
                     let task = this.root_store().upgrade_container(&container);
                     this.root_store().view_task(&task);
                 }
             }),
+            a("sync-timezone").activate(|this, _, _| {
+                if let Some(container) = this.root_store().selected_container() {
+                    this.root_store().sync_timezone(&container);
+                }
+            }),
             a("view-exportable-apps").activate(|this, _, _| {
                 this.root_store().view_exportable_apps();
             }),
@@ -291,6 +377,11 @@ impl DistroShelfWindow {
             a("open-terminal").activate(|this, _, _| {
                 this.open_terminal();
             }),
+            a("toggle-sidebar").activate(|this, _, _| {
+                // The breakpoint is only applied on narrow windows
+                let narrow = this.current_breakpoint().is_some();
+                toggle_split_view(&this.imp().split_view, narrow);
+            }),
         ];
         self.add_action_entries(actions.into_iter().map(|entry| entry.build()));
     }
@@ -392,6 +483,20 @@ impl DistroShelfWindow {
             ));
         sidebar_bottom_box.append(&update_button);
 
+        // Without podman events, changes made outside the app show up only at the next poll
+        let polling_label = gtk::Label::new(Some(&gettext("Refreshing periodically")));
+        polling_label.set_tooltip_text(Some(&gettext(
+            "Container events are unavailable, so the list is refreshed at the interval set in the preferences",
+        )));
+        polling_label.add_css_class("caption");
+        polling_label.add_css_class("dim-label");
+        polling_label.set_margin_top(6);
+        self.root_store()
+            .bind_property("polling-containers", &polling_label, "visible")
+            .sync_create()
+            .build();
+        sidebar_bottom_box.append(&polling_label);
+
         let tasks_button = TasksButton::new(&self.root_store());
         tasks_button.add_css_class("flat");
         sidebar_bottom_box.append(&tasks_button);
@@ -409,6 +514,8 @@ impl DistroShelfWindow {
         let root_store = self.root_store();
         if let Some(container) = root_store.selected_container() {
             let task = root_store.spawn_container_terminal(&container);
+            // The task may end right away, while the terminal is still setting the container up
+            self.warn_if_setup_incomplete(&container);
             let this = self.clone();
             task.connect_status_notify(move |task| {
                 if task.error().is_some() {
@@ -428,6 +535,96 @@ impl DistroShelfWindow {
         }
     }
 
+    /// Asks for the archive to save the exported apps to, or to restore them from
+    fn choose_exports_backup(&self, backup: bool) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("Compressed Archives")));
+        filter.add_pattern("*.tar.gz");
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let file_dialog = gtk::FileDialog::builder()
+            .modal(true)
+            .filters(&filters)
+            .build();
+        let on_chosen = clone!(
+            #[weak(rename_to=this)]
+            self,
+            move |res: Result<gio::File, glib::Error>| {
+                let Some(path) = res.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+                let task = if backup {
+                    this.root_store().backup_exports(path)
+                } else {
+                    this.root_store().restore_exports(path)
+                };
+                this.root_store().view_task(&task);
+            }
+        );
+        if backup {
+            file_dialog.set_title(&gettext("Back Up Exported Apps"));
+            file_dialog.set_initial_name(Some("distroshelf-exported-apps.tar.gz"));
+            file_dialog.save(Some(self), None::<&gio::Cancellable>, on_chosen);
+        } else {
+            file_dialog.set_title(&gettext("Restore Exported Apps"));
+            file_dialog.open(Some(self), None::<&gio::Cancellable>, on_chosen);
+        }
+    }
+
+    /// Opens the create dialog to recreate the container from the exact image it was created from
+    fn pin_image_digest(&self, container: &Container) {
+        let this = self.clone();
+        let container = container.clone();
+        glib::spawn_future_local(async move {
+            let root_store = this.root_store();
+            match root_store.pinned_image(&container).await {
+                Ok(image) => root_store.open_dialog(
+                    DialogType::CreateDistrobox,
+                    DialogParams::new()
+                        .with_volumes_source(container)
+                        .with_image(image),
+                ),
+                Err(e) => {
+                    error!(error = %e, "Failed to resolve the image digest");
+                    this.add_toast(adw::Toast::new(&gettext(
+                        "Couldn't resolve the image digest",
+                    )));
+                }
+            }
+        });
+    }
+
+    /// The first enter runs the setup hooks of the container: warn when they didn't complete
+    fn warn_if_setup_incomplete(&self, container: &Container) {
+        let this = self.clone();
+        let container = container.clone();
+        glib::spawn_future_local(async move {
+            let root_store = this.root_store();
+            match root_store
+                .wait_setup_completed(&container.name(), SETUP_TIMEOUT)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    let message = gettext("The container setup appears incomplete");
+                    root_store.push_notification(
+                        NotificationLevel::Warning,
+                        &container.name(),
+                        &message,
+                    );
+                    let toast = adw::Toast::new(&message);
+                    toast.set_button_label(Some(&gettext("Re-run Setup")));
+                    toast.connect_button_clicked(move |_| {
+                        root_store.rerun_setup(&container);
+                    });
+                    this.add_toast(toast);
+                }
+                Err(e) => warn!(error = %e, "Failed to check the container setup"),
+            }
+        });
+    }
+
     fn build_delete_dialog(&self) {
         let dialog = adw::AlertDialog::builder()
             .heading(gettext("Delete this container?"))
@@ -443,19 +640,76 @@ impl DistroShelfWindow {
             .build();
         dialog.add_response("cancel", &gettext("Cancel"));
         dialog.add_response("delete", &gettext("Delete"));
-
         dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+
+        // A running container can be stopped cleanly before being removed
+        let is_running = self
+            .root_store()
+            .selected_container()
+            .is_some_and(|container| container.is_running());
+        if is_running {
+            dialog.add_response("stop-delete", &gettext("Stop then Delete"));
+            dialog.set_response_appearance("stop-delete", adw::ResponseAppearance::Destructive);
+        }
+
+        // Protected containers are only deleted after typing their name
+        let confirmation_row = adw::EntryRow::new();
+        if let Some(container) = self.root_store().selected_container()
+            && container.protected()
+        {
+            confirmation_row.set_title(&gettext("Type the container name to confirm"));
+            let list = gtk::ListBox::new();
+            list.add_css_class("boxed-list");
+            list.set_selection_mode(gtk::SelectionMode::None);
+            list.append(&confirmation_row);
+            dialog.set_extra_child(Some(&list));
+            dialog.set_body(&gettext_f!(
+                "{name} is protected. Deleting it cannot be undone.",
+                "name" => container.name()
+            ));
+
+            let update_responses = clone!(
+                #[weak]
+                dialog,
+                move |row: &adw::EntryRow| {
+                    let confirmed = row.text() == container.name();
+                    dialog.set_response_enabled("delete", confirmed);
+                    if dialog.has_response("stop-delete") {
+                        dialog.set_response_enabled("stop-delete", confirmed);
+                    }
+                }
+            );
+            update_responses(&confirmation_row);
+            confirmation_row.connect_changed(update_responses);
+        }
+
         dialog.connect_response(
-            Some("delete"),
+            None,
             clone!(
                 #[weak(rename_to = this)]
                 self,
-                move |dialog, _| {
+                move |dialog, response| {
+                    let graceful = match response {
+                        "delete" => false,
+                        "stop-delete" => true,
+                        _ => return,
+                    };
                     if let Some(container) = this.root_store().selected_container() {
-                        this.root_store().delete_container(&container);
-                        this.root_store()
-                            .selected_container_model()
-                            .set_selected(gtk::INVALID_LIST_POSITION);
+                        let confirmation = confirmation_row.text();
+                        match this.root_store().delete_container(
+                            &container,
+                            graceful,
+                            Some(confirmation.as_str()).filter(|text| !text.is_empty()),
+                        ) {
+                            Ok(_) => this
+                                .root_store()
+                                .selected_container_model()
+                                .set_selected(gtk::INVALID_LIST_POSITION),
+                            Err(e) => this.add_toast(adw::Toast::new(&gettext_f!(
+                                "{name} is protected, type its name to confirm",
+                                "name" => e.0
+                            ))),
+                        }
                     }
                     dialog.close();
                 }
@@ -491,9 +745,13 @@ impl DistroShelfWindow {
     fn update_container(&self, container: &Container) {
         let imp = self.imp();
 
-        let container_overview = crate::widgets::ContainerOverview::new(container);
+        let container_overview =
+            crate::widgets::ContainerOverview::new(&self.root_store(), container);
         imp.overview_bin.set_child(Some(&container_overview));
 
+        let container_binaries = ContainerBinaries::new(&self.root_store(), container);
+        imp.binaries_bin.set_child(Some(&container_binaries));
+
         let container_name = container.name();
         let terminal = {
             let mut terminals_by_container = imp.terminals_by_container.borrow_mut();
@@ -508,3 +766,63 @@ impl DistroShelfWindow {
         imp.view_stack.set_visible_child_name("overview");
     }
 }
+
+/// One entry per distro that can be created with the default settings
+fn quick_create_menu() -> gio::Menu {
+    let menu = gio::Menu::new();
+    for entry in QUICK_CREATE_ENTRIES {
+        let item = gio::MenuItem::new(Some(entry.label), None);
+        item.set_action_and_target_value(
+            Some("win.quick-create"),
+            Some(&entry.distro.to_variant()),
+        );
+        menu.append_item(&item);
+    }
+    menu
+}
+
+/// Hides or shows the sidebar. Narrow layouts already show one pane at a time, so the shown pane
+/// is switched; wide layouts get collapsed to the content to hide the sidebar, and expanded back.
+fn toggle_split_view(split_view: &adw::NavigationSplitView, narrow: bool) {
+    if narrow {
+        split_view.set_show_content(!split_view.shows_content());
+    } else if split_view.is_collapsed() {
+        split_view.set_collapsed(false);
+    } else {
+        split_view.set_collapsed(true);
+        split_view.set_show_content(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gtk::test]
+    fn test_toggle_split_view() {
+        let split_view = adw::NavigationSplitView::new();
+        split_view.set_sidebar(Some(&adw::NavigationPage::new(
+            &gtk::Label::new(None),
+            "Sidebar",
+        )));
+        split_view.set_content(Some(&adw::NavigationPage::new(
+            &gtk::Label::new(None),
+            "Content",
+        )));
+
+        // Wide: the sidebar is hidden by collapsing to the content, then restored
+        toggle_split_view(&split_view, false);
+        assert!(split_view.is_collapsed());
+        assert!(split_view.shows_content());
+        toggle_split_view(&split_view, false);
+        assert!(!split_view.is_collapsed());
+
+        // Narrow: the shown pane flips
+        split_view.set_collapsed(true);
+        split_view.set_show_content(false);
+        toggle_split_view(&split_view, true);
+        assert!(split_view.shows_content());
+        toggle_split_view(&split_view, true);
+        assert!(!split_view.shows_content());
+    }
+}